@@ -0,0 +1,275 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::grid::DenseGrid;
+use crate::point::Point;
+use crate::rect::Rect;
+
+type Index = i64;
+
+/// A single compressed axis; see [`CompressedCoords`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis {
+    coords: Vec<Index>,
+    gaps: bool,
+}
+
+impl CompressedAxis {
+    pub fn new<It: IntoIterator<Item = Index>>(values: It, gaps: bool) -> Self {
+        let mut coords = values.into_iter().collect::<Vec<_>>();
+        coords.sort_unstable();
+        coords.dedup();
+        Self { coords, gaps }
+    }
+
+    /// The number of compressed indices. An axis built from no values has
+    /// none, even with gaps, since there is nothing to pad around.
+    pub fn len(&self) -> usize {
+        if self.gaps && !self.coords.is_empty() {
+            2 * self.coords.len() + 1
+        } else {
+            self.coords.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The distinct values this axis was built from, in order
+    pub fn coords(&self) -> &[Index] {
+        &self.coords
+    }
+
+    /// Map a true coordinate to its compressed index. Without gaps, only the
+    /// original values can be compressed; with gaps, anything between the
+    /// smallest and largest original value can be.
+    pub fn compress(&self, v: Index) -> Option<usize> {
+        match (self.coords.binary_search(&v), self.gaps) {
+            (Ok(i), false) => Some(i),
+            (Ok(i), true) => Some(2 * i + 1),
+            (Err(_), false) => None,
+            (Err(0), true) => None,
+            (Err(i), true) if i == self.coords.len() => None,
+            (Err(i), true) => Some(2 * i),
+        }
+    }
+
+    /// The range of true coordinates covered by a compressed index, or None
+    /// if it covers nothing (an empty gap, padding, or out of range)
+    pub fn decompress(&self, index: usize) -> Option<RangeInclusive<Index>> {
+        if !self.gaps {
+            let v = *self.coords.get(index)?;
+            return Some(v..=v);
+        }
+        if index == 0 || index >= 2 * self.coords.len() {
+            return None;
+        }
+        let i = index / 2;
+        if index % 2 == 1 {
+            Some(self.coords[i]..=self.coords[i])
+        } else if self.coords[i] - self.coords[i - 1] > 1 {
+            Some(self.coords[i - 1] + 1..=self.coords[i] - 1)
+        } else {
+            None
+        }
+    }
+
+    /// The number of true units covered by a compressed index
+    pub fn span(&self, index: usize) -> Index {
+        self.decompress(index)
+            .map(|r| r.end() - r.start() + 1)
+            .unwrap_or(0)
+    }
+}
+
+/// Maps a sparse set of points onto a small dense grid.
+///
+/// Each distinct x (and y) coordinate gets its own column (row). With gaps,
+/// the spans between consecutive coordinates get a column (row) of their own
+/// too, and there is one cell of padding on every side, so regions can be
+/// flood-filled from the outside. Padding cells stand for the unbounded space
+/// around the points and so have no size in true units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedCoords {
+    pub xs: CompressedAxis,
+    pub ys: CompressedAxis,
+}
+
+impl CompressedCoords {
+    pub fn new<It: IntoIterator<Item = Point>>(points: It) -> Self {
+        Self::build(points, false)
+    }
+
+    pub fn with_gaps<It: IntoIterator<Item = Point>>(points: It) -> Self {
+        Self::build(points, true)
+    }
+
+    fn build<It: IntoIterator<Item = Point>>(points: It, gaps: bool) -> Self {
+        let (xs, ys): (Vec<_>, Vec<_>) = points.into_iter().map(|p| p.as_tuple()).unzip();
+        Self {
+            xs: CompressedAxis::new(xs, gaps),
+            ys: CompressedAxis::new(ys, gaps),
+        }
+    }
+
+    pub fn compress(&self, p: Point) -> Option<Point> {
+        Some(Point::new(
+            self.xs.compress(p.x)? as Index,
+            self.ys.compress(p.y)? as Index,
+        ))
+    }
+
+    /// The rectangle of true points covered by a compressed cell, if any
+    pub fn decompress(&self, c: Point) -> Option<Rect> {
+        let xs = self.xs.decompress(usize::try_from(c.x).ok()?)?;
+        let ys = self.ys.decompress(usize::try_from(c.y).ok()?)?;
+        Some(Rect::new(
+            Point::new(*xs.start(), *ys.start()),
+            Point::new(*xs.end(), *ys.end()),
+        ))
+    }
+
+    /// The number of true points covered by a compressed cell
+    pub fn cell_area(&self, c: Point) -> Index {
+        self.decompress(c).map(|r| r.area()).unwrap_or(0)
+    }
+
+    /// The true distance covered by the compressed columns `from..=to`
+    pub fn width_between(&self, from: Index, to: Index) -> Index {
+        (from..=to).map(|x| self.xs.span(x as usize)).sum()
+    }
+
+    /// The true distance covered by the compressed rows `from..=to`
+    pub fn height_between(&self, from: Index, to: Index) -> Index {
+        (from..=to).map(|y| self.ys.span(y as usize)).sum()
+    }
+
+    /// A grid covering the whole compressed space, with its origin at
+    /// `(0, 0)`, or None if there were no points to compress
+    pub fn grid<V: Clone + fmt::Debug>(&self, fill: V) -> Option<DenseGrid<V>> {
+        if self.xs.is_empty() || self.ys.is_empty() {
+            return None;
+        }
+        Some(DenseGrid::new_with(
+            Point::new(0, 0),
+            Point::new(self.xs.len() as Index - 1, self.ys.len() as Index - 1),
+            fill,
+        ))
+    }
+
+    /// The total true area of the compressed cells matching `pred`
+    pub fn area_where<V, F>(&self, g: &DenseGrid<V>, pred: F) -> Index
+    where
+        V: Clone + fmt::Debug,
+        F: Fn(&V) -> bool,
+    {
+        g.iter()
+            .filter(|(_, v)| pred(v))
+            .map(|(c, _)| self.cell_area(c))
+            .sum()
+    }
+
+    /// Prefix sums of the true area of the compressed cells matching `pred`,
+    /// for constant-time area queries over compressed rectangles
+    pub fn area_sums<V, F>(&self, g: &DenseGrid<V>, pred: F) -> AreaSums
+    where
+        V: Clone + fmt::Debug,
+        F: Fn(&V) -> bool,
+    {
+        let stride = g.width() + 1;
+        let mut sums = vec![0; stride * (g.height() + 1)];
+        for (y, row) in g.rows().enumerate() {
+            for (x, v) in row.iter().enumerate() {
+                let area = if pred(v) {
+                    self.cell_area(Point::new(x as Index, y as Index))
+                } else {
+                    0
+                };
+                sums[(y + 1) * stride + x + 1] =
+                    area + sums[y * stride + x + 1] + sums[(y + 1) * stride + x]
+                        - sums[y * stride + x];
+            }
+        }
+        AreaSums { stride, sums }
+    }
+}
+
+/// See [`CompressedCoords::area_sums`]
+#[derive(Debug, Clone)]
+pub struct AreaSums {
+    stride: usize,
+    sums: Vec<Index>,
+}
+
+impl AreaSums {
+    /// The total true area of matching cells within the inclusive compressed
+    /// rectangle `min..=max`
+    pub fn area_in(&self, min: Point, max: Point) -> Index {
+        let at = |x: Index, y: Index| self.sums[y as usize * self.stride + x as usize];
+        at(max.x + 1, max.y + 1) - at(min.x, max.y + 1) - at(max.x + 1, min.y) + at(min.x, min.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressedCoords, Point, Rect};
+
+    #[test]
+    fn test_without_gaps() {
+        let c = CompressedCoords::new([Point::new(100, 5), Point::new(-20, 5), Point::new(100, 9)]);
+        assert_eq!(c.xs.len(), 2);
+        assert_eq!(c.ys.len(), 2);
+        assert_eq!(c.compress(Point::new(100, 9)), Some(Point::new(1, 1)));
+        assert_eq!(c.compress(Point::new(50, 9)), None);
+        assert_eq!(
+            c.decompress(Point::new(0, 1)),
+            Some(Rect::new(Point::new(-20, 9), Point::new(-20, 9)))
+        );
+    }
+
+    #[test]
+    fn test_with_gaps() {
+        let c =
+            CompressedCoords::with_gaps([Point::new(0, 0), Point::new(10, 1), Point::new(11, 4)]);
+        assert_eq!(c.xs.len(), 7);
+        assert_eq!(c.compress(Point::new(5, 3)), Some(Point::new(2, 4)));
+        assert_eq!(c.compress(Point::new(12, 0)), None);
+        assert_eq!(
+            c.decompress(Point::new(2, 4)),
+            Some(Rect::new(Point::new(1, 2), Point::new(9, 3)))
+        );
+        // padding, and the empty gap between 10 and 11
+        assert_eq!(c.decompress(Point::new(0, 1)), None);
+        assert_eq!(c.decompress(Point::new(4, 1)), None);
+        assert_eq!(c.width_between(0, 6), 12);
+        assert_eq!(c.height_between(0, 6), 5);
+    }
+
+    #[test]
+    fn test_areas() {
+        let c = CompressedCoords::with_gaps([Point::new(0, 0), Point::new(99, 99)]);
+        let mut g = c.grid(false).unwrap();
+        assert_eq!(g.width(), 5);
+        g[Point::new(2, 2)] = true;
+        g[Point::new(1, 1)] = true;
+        assert_eq!(c.area_where(&g, |v| *v), 98 * 98 + 1);
+        let sums = c.area_sums(&g, |v| *v);
+        assert_eq!(
+            sums.area_in(Point::new(0, 0), Point::new(4, 4)),
+            98 * 98 + 1
+        );
+        assert_eq!(sums.area_in(Point::new(1, 1), Point::new(1, 2)), 1);
+    }
+
+    #[test]
+    fn test_no_points() {
+        for c in [CompressedCoords::new([]), CompressedCoords::with_gaps([])] {
+            assert_eq!(c.xs.len(), 0);
+            assert!(c.xs.is_empty());
+            assert_eq!(c.compress(Point::new(0, 0)), None);
+            assert_eq!(c.decompress(Point::new(0, 0)), None);
+            assert!(c.grid(()).is_none());
+        }
+    }
+}
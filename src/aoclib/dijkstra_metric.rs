@@ -1,4 +1,5 @@
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DijkstraMetric<V>
 where
     V: std::fmt::Debug + Clone + Copy,
@@ -56,3 +57,20 @@ impl<V: std::fmt::Debug + Clone + Copy> DijkstraMetric<V> {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::DijkstraMetric;
+
+    #[test]
+    fn test_serde() {
+        for m in [DijkstraMetric::Finite(7u32), DijkstraMetric::Infinite] {
+            let s = serde_json::to_string(&m).unwrap();
+            assert_eq!(serde_json::from_str::<DijkstraMetric<u32>>(&s).unwrap(), m);
+        }
+        assert_eq!(
+            serde_json::to_string(&DijkstraMetric::Finite(7u32)).unwrap(),
+            r#"{"Finite":7}"#
+        );
+    }
+}
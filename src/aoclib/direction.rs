@@ -0,0 +1,243 @@
+use crate::dimval::DimVal;
+use crate::point::{Point, Rotation};
+use std::fmt;
+
+/// A compass direction on a grid where y increases downwards (i.e., `N` is
+/// `(0, -1)` and `S` is `(0, 1)`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// All 8 directions, clockwise from `N`
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    /// The 4 non-diagonal directions, clockwise from `N`
+    pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    pub const DIAGONAL: [Direction; 4] =
+        [Direction::NE, Direction::SE, Direction::SW, Direction::NW];
+
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        matches!(self, Self::NE | Self::SE | Self::SW | Self::NW)
+    }
+
+    /// The unit offset for this direction; diagonals have both components set
+    pub fn to_point<I: DimVal>(&self) -> Point<I> {
+        let one = I::one();
+        let zero = I::zero();
+        let (x, y) = match self {
+            Self::N => (zero, -one),
+            Self::NE => (one, -one),
+            Self::E => (one, zero),
+            Self::SE => (one, one),
+            Self::S => (zero, one),
+            Self::SW => (-one, one),
+            Self::W => (-one, zero),
+            Self::NW => (-one, -one),
+        };
+        Point::new(x, y)
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    /// Rotate by the given number of eighth-turns clockwise (negative for counter-clockwise)
+    pub fn turn_eighths(&self, eighths: i32) -> Self {
+        Self::ALL[(self.index() as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn turn(&self, r: Rotation) -> Self {
+        match r {
+            Rotation::CW => self.turn_eighths(2),
+            Rotation::CCW => self.turn_eighths(-2),
+            Rotation::Mirror => self.opposite(),
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.turn_eighths(4)
+    }
+
+    /// The arrow character (`^>v<`) for a cardinal direction
+    pub fn to_arrow(&self) -> Option<char> {
+        match self {
+            Self::N => Some('^'),
+            Self::E => Some('>'),
+            Self::S => Some('v'),
+            Self::W => Some('<'),
+            _ => None,
+        }
+    }
+
+    /// Parse a single-character direction: an arrow (`^>v<`), a compass letter
+    /// (`NESW`), or a relative letter (`UDLR`)
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Self::N),
+            '>' | 'E' | 'R' => Some(Self::E),
+            'v' | 'S' | 'D' => Some(Self::S),
+            '<' | 'W' | 'L' => Some(Self::W),
+            _ => None,
+        }
+    }
+
+    /// Parse a run of single-character directions (e.g., `"^^>v<"`), ignoring whitespace
+    pub fn parse_all(s: &str) -> anyhow::Result<Vec<Self>> {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| Self::from_char(c).ok_or_else(|| anyhow::anyhow!("invalid direction {:?}", c)))
+            .collect()
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::N => "N",
+            Self::NE => "NE",
+            Self::E => "E",
+            Self::SE => "SE",
+            Self::S => "S",
+            Self::SW => "SW",
+            Self::W => "W",
+            Self::NW => "NW",
+        };
+        f.write_str(s)
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let d = match s {
+            "N" | "U" | "^" => Self::N,
+            "NE" => Self::NE,
+            "E" | "R" | ">" => Self::E,
+            "SE" => Self::SE,
+            "S" | "D" | "v" => Self::S,
+            "SW" => Self::SW,
+            "W" | "L" | "<" => Self::W,
+            "NW" => Self::NW,
+            _ => anyhow::bail!("invalid direction {:?}", s),
+        };
+        Ok(d)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Self::from_char(c).ok_or_else(|| anyhow::anyhow!("invalid direction {:?}", c))
+    }
+}
+
+impl<I: DimVal> From<Direction> for Point<I> {
+    fn from(d: Direction) -> Self {
+        d.to_point()
+    }
+}
+
+impl<I: DimVal> TryFrom<Point<I>> for Direction {
+    type Error = anyhow::Error;
+
+    /// Convert a unit offset (including diagonals) back into a direction
+    fn try_from(p: Point<I>) -> Result<Self, Self::Error> {
+        Direction::iter()
+            .find(|d| d.to_point::<I>() == p)
+            .ok_or_else(|| anyhow::anyhow!("{:?} is not a unit direction", p))
+    }
+}
+
+impl<I: DimVal> std::ops::Add<Direction> for Point<I> {
+    type Output = Self;
+
+    fn add(self, d: Direction) -> Self {
+        self + d.to_point()
+    }
+}
+
+impl<I: DimVal> std::ops::AddAssign<Direction> for Point<I> {
+    fn add_assign(&mut self, d: Direction) {
+        *self = *self + d;
+    }
+}
+
+impl<I: DimVal> Point<I> {
+    /// The neighboring point in the given direction
+    pub fn step(&self, d: Direction) -> Self {
+        *self + d
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point, Rotation};
+
+    #[test]
+    fn test_points() {
+        assert_eq!(Point::from(Direction::N), Point::new(0, -1));
+        assert_eq!(Point::new(3, 3) + Direction::SW, Point::new(2, 4));
+        for d in Direction::iter() {
+            assert_eq!(Direction::try_from(d.to_point::<i64>()).unwrap(), d);
+            assert_eq!(
+                d.to_point::<i64>() + d.opposite().to_point(),
+                Point::new(0, 0)
+            );
+        }
+        assert!(Direction::try_from(Point::new(2, 0)).is_err());
+    }
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(Direction::N.turn(Rotation::CW), Direction::E);
+        assert_eq!(Direction::N.turn(Rotation::CCW), Direction::W);
+        assert_eq!(Direction::NE.turn(Rotation::Mirror), Direction::SW);
+        assert_eq!(Direction::NW.turn_eighths(1), Direction::N);
+        // turning agrees with rotating the unit offset
+        for d in Direction::CARDINAL {
+            assert_eq!(
+                d.turn(Rotation::CW).to_point::<i64>(),
+                d.to_point::<i64>().rotate_by(Rotation::CW)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Direction::parse_all("^>\nv<").unwrap(),
+            vec![Direction::N, Direction::E, Direction::S, Direction::W]
+        );
+        assert!(Direction::parse_all("^x").is_err());
+        assert_eq!("NW".parse::<Direction>().unwrap(), Direction::NW);
+        assert_eq!(Direction::SE.to_string(), "SE");
+        assert_eq!(Direction::W.to_arrow(), Some('<'));
+        assert_eq!(Direction::try_from('R').unwrap(), Direction::E);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A union-find structure partitioning a set of keys into disjoint components,
+/// with path compression and union by size
+#[derive(Debug, Clone)]
+pub struct DisjointSet<K> {
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+    parent: Vec<usize>,
+    /// only meaningful for the root of each component
+    size: Vec<usize>,
+    num_components: usize,
+}
+
+impl<K: Hash + Eq + Clone> Default for DisjointSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> DisjointSet<K> {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            keys: vec![],
+            parent: vec![],
+            size: vec![],
+            num_components: 0,
+        }
+    }
+
+    /// The number of keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /// All keys, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    /// Add a key in a component of its own. Returns false (and does nothing)
+    /// if the key was already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+        self.index_or_insert(&key);
+        true
+    }
+
+    fn index_or_insert(&mut self, key: &K) -> usize {
+        if let Some(index) = self.indices.get(key) {
+            return *index;
+        }
+        let index = self.keys.len();
+        self.indices.insert(key.clone(), index);
+        self.keys.push(key.clone());
+        self.parent.push(index);
+        self.size.push(1);
+        self.num_components += 1;
+        index
+    }
+
+    fn root(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = index;
+        while current != root {
+            current = std::mem::replace(&mut self.parent[current], root);
+        }
+        root
+    }
+
+    /// The representative key of the component containing `key`
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = *self.indices.get(key)?;
+        let root = self.root(index);
+        Some(&self.keys[root])
+    }
+
+    /// Merge the components containing the two keys, adding either key if
+    /// it isn't already present. Returns true if two distinct components
+    /// were merged.
+    pub fn union(&mut self, a: &K, b: &K) -> bool {
+        let a = self.index_or_insert(a);
+        let b = self.index_or_insert(b);
+        let (a, b) = (self.root(a), self.root(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.num_components -= 1;
+        true
+    }
+
+    /// Whether both keys are present and in the same component
+    pub fn same_component(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a).copied(), self.indices.get(b).copied()) {
+            (Some(a), Some(b)) => self.root(a) == self.root(b),
+            _ => false,
+        }
+    }
+
+    /// The number of keys in the component containing `key`
+    pub fn component_size(&mut self, key: &K) -> Option<usize> {
+        let index = *self.indices.get(key)?;
+        let root = self.root(index);
+        Some(self.size[root])
+    }
+
+    /// The size of each component, in the order their representatives were added
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.keys.len())
+            .filter(|i| self.parent[*i] == *i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+
+    /// The keys in the same component as `key` (including itself), in the
+    /// order they were added
+    pub fn members(&mut self, key: &K) -> Vec<&K> {
+        let Some(&index) = self.indices.get(key) else {
+            return vec![];
+        };
+        let root = self.root(index);
+        let in_component = (0..self.keys.len())
+            .filter(|i| self.root(*i) == root)
+            .collect::<Vec<_>>();
+        in_component.into_iter().map(|i| &self.keys[i]).collect()
+    }
+
+    /// Every component, ordered by its earliest-added key, with keys in the
+    /// order they were added
+    pub fn components(&mut self) -> Vec<Vec<&K>> {
+        let roots = (0..self.keys.len())
+            .map(|i| self.root(i))
+            .collect::<Vec<_>>();
+        let mut slot_for_root = HashMap::new();
+        let mut components: Vec<Vec<&K>> = vec![];
+        for (key, root) in self.keys.iter().zip(roots) {
+            let slot = *slot_for_root.entry(root).or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[slot].push(key);
+        }
+        components
+    }
+}
+
+impl<K: Hash + Eq + Clone> Extend<K> for DisjointSet<K> {
+    fn extend<It: IntoIterator<Item = K>>(&mut self, iter: It) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> FromIterator<K> for DisjointSet<K> {
+    fn from_iter<It: IntoIterator<Item = K>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjointSet;
+
+    #[test]
+    fn test_union_find() {
+        let mut set = "abcdef".chars().collect::<DisjointSet<_>>();
+        assert_eq!(set.len(), 6);
+        assert_eq!(set.num_components(), 6);
+        assert!(set.union(&'a', &'b'));
+        assert!(set.union(&'c', &'d'));
+        assert!(set.union(&'b', &'d'));
+        assert!(!set.union(&'a', &'c'));
+        assert_eq!(set.num_components(), 3);
+        assert!(set.same_component(&'a', &'d'));
+        assert!(!set.same_component(&'a', &'e'));
+        assert!(!set.same_component(&'a', &'z'));
+        let representative = set.find(&'b').copied();
+        assert_eq!(set.find(&'c').copied(), representative);
+        assert_eq!(set.component_size(&'d'), Some(4));
+        assert_eq!(set.component_size(&'z'), None);
+        let mut sizes = set.component_sizes();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 1, 4]);
+        assert_eq!(set.members(&'c'), vec![&'a', &'b', &'c', &'d']);
+        assert_eq!(
+            set.components(),
+            vec![vec![&'a', &'b', &'c', &'d'], vec![&'e'], vec![&'f']]
+        );
+    }
+
+    #[test]
+    fn test_union_inserts_missing_keys() {
+        let mut set = DisjointSet::new();
+        assert!(set.union(&"x", &"y"));
+        assert!(!set.insert("x"));
+        assert!(set.insert("z"));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.num_components(), 2);
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec!["x", "y", "z"]);
+    }
+}
@@ -1,12 +1,83 @@
-use std::cmp::{max, min};
-use std::collections::{BTreeSet, BinaryHeap};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
 use std::fmt;
 
+use rayon::prelude::*;
+
 use super::DijkstraMetric;
 use super::point::Point;
+use super::rect::Rect;
 
 type Index = i64;
 
+/// How [`DenseGrid::parse_input`] treats rows of differing widths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMode<V> {
+    /// Every row must have the same number of cells as the first
+    Strict,
+    /// Rows shorter than the longest row are padded with `fill`
+    Lenient { fill: V },
+}
+
+/// Why [`DenseGrid::parse_input`] failed. Positions are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError<E> {
+    /// There were no cells at all
+    Empty,
+    /// A row's width differed from the first row's, in [`ParseMode::Strict`]
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The cell parser failed
+    Cell {
+        line: usize,
+        column: usize,
+        error: E,
+    },
+}
+
+impl<E> ParseError<E> {
+    /// The cell parser's error, if that's what failed
+    pub fn into_cell_error(self) -> Option<E> {
+        match self {
+            ParseError::Cell { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty input"),
+            ParseError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} cells, found {}",
+                line, expected, found
+            ),
+            ParseError::Cell {
+                line,
+                column,
+                error,
+            } => write!(f, "line {}, column {}: {}", line, column, error),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ParseError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Cell { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
 pub trait HasEmpty: PartialEq {
     fn empty_value() -> Self;
 }
@@ -19,10 +90,7 @@ impl<T: Default + PartialEq> HasEmpty for T {
 
 #[derive(Debug, Clone)]
 pub struct DenseGrid<V: Clone + fmt::Debug> {
-    pub min_x: Index,
-    pub min_y: Index,
-    pub max_x: Index,
-    pub max_y: Index,
+    bounds: Rect<Index>,
     width: usize,
     height: usize,
     cells: Vec<V>,
@@ -33,21 +101,14 @@ impl<V: Clone + fmt::Debug + std::hash::Hash> std::hash::Hash for DenseGrid<V> {
     where
         H: std::hash::Hasher,
     {
-        self.min_x.hash(state);
-        self.min_y.hash(state);
-        self.max_x.hash(state);
-        self.max_y.hash(state);
+        self.bounds.hash(state);
         self.cells.hash(state);
     }
 }
 
 impl<V: Clone + fmt::Debug + PartialEq> PartialEq for DenseGrid<V> {
     fn eq(&self, other: &Self) -> bool {
-        self.min_x == other.min_x
-            && self.min_y == other.min_y
-            && self.max_x == other.max_x
-            && self.max_y == other.max_y
-            && self.cells == other.cells
+        self.bounds == other.bounds && self.cells == other.cells
     }
 }
 
@@ -58,46 +119,41 @@ impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid<V> {
         Self::new_with(upper_left, lower_right, V::empty_value())
     }
 
+    /// Parse a grid of characters, padding short rows with the empty value.
+    /// Panics on empty input; see [`DenseGrid::parse_input`] for a fallible version.
     pub fn from_input<F>(input: &str, f: F) -> Self
     where
         F: Fn(char) -> V,
     {
-        let height = input.lines().count() as i64 - 1;
-        let width = input.lines().next().unwrap().chars().count() as i64 - 1;
-        let mut g = Self::new_with(
-            Point::new(0, 0),
-            Point::new(width, height),
-            V::empty_value(),
-        );
-        for (y, row) in input.lines().enumerate() {
-            for (x, chr) in row.chars().enumerate() {
-                let coord = Point::new(x as i64, y as i64);
-                let value = f(chr);
-                g.set(coord, value);
-            }
-        }
-        g
+        Self::parse_input(
+            input,
+            ParseMode::Lenient {
+                fill: V::empty_value(),
+            },
+            |c| Ok::<V, std::convert::Infallible>(f(c)),
+        )
+        .expect("invalid grid input")
     }
 
+    /// Parse a grid of characters, padding short rows with the empty value,
+    /// and returning the first error from `f`. Panics on empty input; see
+    /// [`DenseGrid::parse_input`] for a version which reports that (and where
+    /// `f` failed) as an error.
     pub fn try_from_input<E, F>(input: &str, f: F) -> Result<Self, E>
     where
         F: Fn(char) -> Result<V, E>,
     {
-        let height = input.lines().count() as i64 - 1;
-        let width = input.lines().next().unwrap().chars().count() as i64 - 1;
-        let mut g = Self::new_with(
-            Point::new(0, 0),
-            Point::new(width, height),
-            V::empty_value(),
-        );
-        for (y, row) in input.lines().enumerate() {
-            for (x, chr) in row.chars().enumerate() {
-                let coord = Point::new(x as i64, y as i64);
-                let value = f(chr)?;
-                g.set(coord, value);
-            }
-        }
-        Ok(g)
+        Self::parse_input(
+            input,
+            ParseMode::Lenient {
+                fill: V::empty_value(),
+            },
+            f,
+        )
+        .map_err(|e| match e {
+            ParseError::Cell { error, .. } => error,
+            _ => panic!("invalid grid input"),
+        })
     }
 
     /// start flood-fill at the given point; fill empty
@@ -127,48 +183,134 @@ impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid<V> {
 
 impl<V: Clone + fmt::Debug> DenseGrid<V> {
     pub fn new_with(upper_left: Point<Index>, lower_right: Point<Index>, empty_value: V) -> Self {
-        let min_x = min(upper_left.x, lower_right.x);
-        let max_x = max(upper_left.x, lower_right.x);
-        let min_y = min(upper_left.y, lower_right.y);
-        let max_y = max(upper_left.y, lower_right.y);
-        let width = 1 + max_x.abs_diff(min_x) as usize;
-        let height = 1 + max_y.abs_diff(min_y) as usize;
+        let bounds = Rect::new(upper_left, lower_right);
+        let width = 1 + bounds.max.x.abs_diff(bounds.min.x) as usize;
+        let height = 1 + bounds.max.y.abs_diff(bounds.min.y) as usize;
         Self {
-            min_x,
-            max_x,
-            min_y,
-            max_y,
+            bounds,
             width,
             height,
             cells: vec![empty_value; width * height],
         }
     }
 
+    /// Parse a grid of characters, one row per line. Handles `\r\n` line
+    /// endings and ignores trailing blank lines. Returns an error for empty
+    /// input, for rows of inconsistent width (in [`ParseMode::Strict`]), or if
+    /// `f` fails; see [`ParseError`].
+    pub fn parse_input<E, F>(input: &str, mode: ParseMode<V>, f: F) -> Result<Self, ParseError<E>>
+    where
+        F: Fn(char) -> Result<V, E>,
+    {
+        Self::parse_input_with_markers(input, mode, &[], f).map(|(g, _)| g)
+    }
+
+    /// Same as [`DenseGrid::parse_input`], but also return the position of
+    /// every occurrence of each of the `markers` characters (e.g., a start
+    /// position). Marker characters are still passed to `f`.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_input_with_markers<E, F>(
+        input: &str,
+        mode: ParseMode<V>,
+        markers: &[char],
+        f: F,
+    ) -> Result<(Self, BTreeMap<char, Vec<Point>>), ParseError<E>>
+    where
+        F: Fn(char) -> Result<V, E>,
+    {
+        let mut lines = input.lines().collect::<Vec<_>>();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let Some(first) = lines.first() else {
+            return Err(ParseError::Empty);
+        };
+        let width = match mode {
+            ParseMode::Strict => {
+                let expected = first.chars().count();
+                for (i, line) in lines.iter().enumerate() {
+                    let found = line.chars().count();
+                    if found != expected {
+                        return Err(ParseError::Ragged {
+                            line: i + 1,
+                            expected,
+                            found,
+                        });
+                    }
+                }
+                expected
+            }
+            ParseMode::Lenient { .. } => lines.iter().map(|l| l.chars().count()).max().unwrap(),
+        };
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+        let height = lines.len();
+        let mut cells = Vec::with_capacity(width * height);
+        let mut found = BTreeMap::new();
+        for (y, line) in lines.iter().enumerate() {
+            let mut row_width = 0;
+            for (x, chr) in line.chars().enumerate() {
+                if markers.contains(&chr) {
+                    found
+                        .entry(chr)
+                        .or_insert_with(Vec::new)
+                        .push(Point::new(x as i64, y as i64));
+                }
+                let value = f(chr).map_err(|error| ParseError::Cell {
+                    line: y + 1,
+                    column: x + 1,
+                    error,
+                })?;
+                cells.push(value);
+                row_width += 1;
+            }
+            if let ParseMode::Lenient { fill } = &mode {
+                cells.extend(std::iter::repeat_n(fill.clone(), width - row_width));
+            }
+        }
+        let g = Self {
+            bounds: Rect::new(
+                Point::new(0, 0),
+                Point::new(width as Index - 1, height as Index - 1),
+            ),
+            width,
+            height,
+            cells,
+        };
+        Ok((g, found))
+    }
+
+    pub fn new_with_bounds(bounds: Rect<Index>, empty_value: V) -> Self {
+        Self::new_with(bounds.min, bounds.max, empty_value)
+    }
+
     pub fn new_with_dimensions_from<T: Clone + fmt::Debug>(
         g: &DenseGrid<T>,
         empty_value: V,
     ) -> Self {
         Self {
-            min_x: g.min_x,
-            max_x: g.max_x,
-            min_y: g.min_y,
-            max_y: g.max_y,
+            bounds: g.bounds,
             width: g.width,
             height: g.height,
             cells: vec![empty_value; g.width * g.height],
         }
     }
 
+    pub fn bounds(&self) -> Rect<Index> {
+        self.bounds
+    }
+
     pub fn origin(&self) -> Point {
-        Point::new(self.min_x, self.min_y)
+        self.bounds.min
     }
 
     pub fn row_numbers(&self) -> impl Iterator<Item = Index> {
-        self.min_y..=self.max_y
+        self.bounds.min.y..=self.bounds.max.y
     }
 
     pub fn column_numbers(&self) -> impl Iterator<Item = Index> {
-        self.min_x..=self.max_x
+        self.bounds.min.x..=self.bounds.max.x
     }
 
     pub fn width(&self) -> usize {
@@ -189,6 +331,11 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
         self.cells.get(index).cloned()
     }
 
+    pub(crate) fn get_ref(&self, coordinate: Point<Index>) -> Option<&V> {
+        let index = self.index_for(coordinate)?;
+        self.cells.get(index)
+    }
+
     pub fn get_mut(&mut self, coordinate: Point<Index>) -> Option<&mut V> {
         let index = self.index_for(coordinate)?;
         self.cells.get_mut(index)
@@ -202,22 +349,124 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
     }
 
     pub fn contains(&self, coordinate: Point<Index>) -> bool {
-        coordinate.x >= self.min_x
-            && coordinate.x <= self.max_x
-            && coordinate.y >= self.min_y
-            && coordinate.y <= self.max_y
+        self.bounds.contains(coordinate)
     }
 
     pub fn dump_with<F: Fn(&V) -> char>(&self, f: F) {
-        for y in self.min_y..=self.max_y {
-            let cells = (self.min_x..=self.max_x)
-                .map(|x| {
-                    let coordinate = Point::new(x, y);
-                    f(&self[coordinate])
-                })
-                .collect::<String>();
-            println!("{}", cells);
+        for line in self.text_lines(&f, false) {
+            println!("{}", line);
+        }
+    }
+
+    /// Render each row to a line of text. If `trim` is set, trailing whitespace
+    /// is stripped from each line, so rows may come out ragged.
+    fn text_lines<'a, F: Fn(&V) -> char>(
+        &'a self,
+        f: &'a F,
+        trim: bool,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.rows().map(move |row| {
+            let mut line = row.iter().map(f).collect::<String>();
+            if trim {
+                line.truncate(line.trim_end().len());
+            }
+            line
+        })
+    }
+
+    /// Write the grid as text (one line per row) to any writer. This is the
+    /// inverse of [`DenseGrid::from_input`].
+    pub fn write_with<W: std::io::Write, F: Fn(&V) -> char>(
+        &self,
+        mut w: W,
+        f: F,
+    ) -> std::io::Result<()> {
+        for line in self.text_lines(&f, false) {
+            writeln!(w, "{}", line)?;
         }
+        Ok(())
+    }
+
+    /// Same as [`DenseGrid::write_with`], but strip trailing whitespace from each line
+    pub fn write_trimmed_with<W: std::io::Write, F: Fn(&V) -> char>(
+        &self,
+        mut w: W,
+        f: F,
+    ) -> std::io::Result<()> {
+        for line in self.text_lines(&f, true) {
+            writeln!(w, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_string_with<F: Fn(&V) -> char>(&self, f: F) -> String {
+        self.display_with(f).to_string()
+    }
+
+    pub fn to_trimmed_string_with<F: Fn(&V) -> char>(&self, f: F) -> String {
+        self.text_lines(&f, true)
+            .map(|mut line| {
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Wrap the grid in something implementing [`fmt::Display`], using `f` to
+    /// render each cell
+    pub fn display_with<F: Fn(&V) -> char>(&self, f: F) -> DisplayWith<'_, V, F> {
+        DisplayWith { grid: self, f }
+    }
+
+    /// Produce a human-readable, side-by-side rendering of the two grids with
+    /// differing cells marked, or None if they are equal.
+    pub fn diff_with<F: Fn(&V) -> char>(&self, other: &Self, f: F) -> Option<String>
+    where
+        V: PartialEq,
+    {
+        if self == other {
+            return None;
+        }
+        let mut out = String::new();
+        if self.bounds() != other.bounds() {
+            out.push_str(&format!(
+                "bounds differ: left is {}, right is {}\n",
+                self.bounds(),
+                other.bounds()
+            ));
+        }
+        let Rect {
+            min: Point { x: min_x, y: min_y },
+            max: Point { x: max_x, y: max_y },
+        } = self.bounds().union(&other.bounds());
+        let render = |g: &Self, p: Point| g.get_ref(p).map(&f).unwrap_or(' ');
+        let width = (max_x - min_x + 1) as usize;
+        let left_width = width.max("left".len());
+        let right_width = width.max("right".len());
+        out.push_str(&format!(
+            "{:<left_width$} | {:<right_width$} | diff\n",
+            "left", "right"
+        ));
+        for y in min_y..=max_y {
+            let mut left = String::new();
+            let mut right = String::new();
+            let mut marks = String::new();
+            for x in min_x..=max_x {
+                let p = Point::new(x, y);
+                left.push(render(self, p));
+                right.push(render(other, p));
+                marks.push(if self.get_ref(p) == other.get_ref(p) {
+                    '.'
+                } else {
+                    'X'
+                });
+            }
+            out.push_str(&format!(
+                "{:<left_width$} | {:<right_width$} | {}\n",
+                left, right, marks
+            ));
+        }
+        Some(out)
     }
 
     pub fn save_to_image<F: Fn(&V) -> image::Rgb<u8>, P: AsRef<std::path::Path>>(
@@ -232,45 +481,88 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
         );
         for (point, value) in self.iter() {
             let point = point - self.origin();
-            image.put_pixel(point.x as u32, point.y as u32, f(&value));
+            image.put_pixel(point.x as u32, point.y as u32, f(value));
         }
         image.save(path.as_ref())?;
         Ok(())
     }
 
     fn index_for(&self, coordinate: Point<Index>) -> Option<usize> {
-        if coordinate.x < self.min_x
-            || coordinate.x > self.max_x
-            || coordinate.y < self.min_y
-            || coordinate.y > self.max_y
-        {
+        if !self.bounds.contains(coordinate) {
             None
         } else {
-            let row = coordinate.y.abs_diff(self.min_y) as usize * self.width;
-            let col = coordinate.x.abs_diff(self.min_x) as usize;
+            let row = coordinate.y.abs_diff(self.bounds.min.y) as usize * self.width;
+            let col = coordinate.x.abs_diff(self.bounds.min.x) as usize;
             Some(row + col)
         }
     }
 
     pub fn rows(&self) -> Rows<'_, V> {
         Rows {
-            grid: self,
-            y: self.min_y,
+            inner: self.cells.chunks(self.width),
         }
     }
 
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [V]> {
+        self.cells.chunks_mut(self.width)
+    }
+
+    /// Get a row by y coordinate as a slice. Panics if the row is out-of-bounds.
+    pub fn row(&self, y: Index) -> &[V] {
+        assert!(
+            y >= self.bounds.min.y && y <= self.bounds.max.y,
+            "row {} out of bounds",
+            y
+        );
+        let start = y.abs_diff(self.bounds.min.y) as usize * self.width;
+        &self.cells[start..start + self.width]
+    }
+
+    pub fn row_mut(&mut self, y: Index) -> &mut [V] {
+        assert!(
+            y >= self.bounds.min.y && y <= self.bounds.max.y,
+            "row {} out of bounds",
+            y
+        );
+        let start = y.abs_diff(self.bounds.min.y) as usize * self.width;
+        &mut self.cells[start..start + self.width]
+    }
+
+    /// Get a strided view of a column by x coordinate. Panics if the column is
+    /// out-of-bounds.
+    pub fn column(&self, x: Index) -> Column<'_, V> {
+        assert!(
+            x >= self.bounds.min.x && x <= self.bounds.max.x,
+            "column {} out of bounds",
+            x
+        );
+        let start = x.abs_diff(self.bounds.min.x) as usize;
+        Column {
+            inner: self.cells[start..].iter().step_by(self.width),
+        }
+    }
+
+    /// Iterate over the columns, left to right, each as a borrowing iterator
+    /// from top to bottom
     pub fn columns(&self) -> Columns<'_, V> {
         Columns {
             grid: self,
-            x: self.min_x,
+            x: self.bounds.min.x,
         }
     }
 
     pub fn iter(&self) -> Iter<'_, V> {
         Iter {
             grid: self,
-            x: self.min_x,
-            y: self.min_y,
+            inner: self.cells.iter().enumerate(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
+        IterMut {
+            origin: self.origin(),
+            width: self.width,
+            inner: self.cells.iter_mut().enumerate(),
         }
     }
 
@@ -348,90 +640,287 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
     }
 }
 
+impl<V: Clone + fmt::Debug + Send + Sync> DenseGrid<V> {
+    /// Iterate over all cells in parallel, in row-major order
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (Point<Index>, &V)> {
+        self.cells
+            .par_iter()
+            .enumerate()
+            .map(|(i, v)| (point_for(self.origin(), self.width, i), v))
+    }
+
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item = (Point<Index>, &mut V)> {
+        let (origin, width) = (self.origin(), self.width);
+        self.cells
+            .par_iter_mut()
+            .enumerate()
+            .map(move |(i, v)| (point_for(origin, width, i), v))
+    }
+
+    /// Iterate over rows in parallel; each item is the row number and its cells
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = (Index, &[V])> {
+        self.cells
+            .par_chunks(self.width)
+            .enumerate()
+            .map(|(i, row)| (self.bounds.min.y + i as Index, row))
+    }
+
+    /// Build a new grid with the same bounds by applying `f` to every cell in parallel
+    pub fn par_map<U, F>(&self, f: F) -> DenseGrid<U>
+    where
+        U: Clone + fmt::Debug + Send,
+        F: Fn(Point<Index>, &V) -> U + Sync + Send,
+    {
+        DenseGrid {
+            bounds: self.bounds,
+            width: self.width,
+            height: self.height,
+            cells: self.par_iter().map(|(p, v)| f(p, v)).collect(),
+        }
+    }
+}
+
+/// The coordinate of the cell at `index` in the row-major cells of a grid.
+/// A free function so iterators can use it without borrowing the whole grid.
+fn point_for(origin: Point<Index>, width: usize, index: usize) -> Point<Index> {
+    origin + Point::new((index % width) as Index, (index / width) as Index)
+}
+
 impl<V: Clone + PartialEq + fmt::Debug> DenseGrid<V> {
     pub fn find(&self, value: &V) -> Option<Point> {
         self.iter()
-            .find_map(|(p, v)| if v == *value { Some(p) } else { None })
+            .find_map(|(p, v)| if v == value { Some(p) } else { None })
     }
 }
 
-pub struct Rows<'a, V: Clone + std::fmt::Debug> {
+/// Grids are serialized with their bounds and one sequence per row, rather
+/// than as a flat list of cells
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{DenseGrid, Index, Point, Rect};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+
+    #[derive(Serialize)]
+    #[serde(rename = "DenseGrid")]
+    struct GridRef<'a, V> {
+        min_x: Index,
+        min_y: Index,
+        max_x: Index,
+        max_y: Index,
+        rows: Vec<&'a [V]>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "DenseGrid")]
+    struct GridOwned<V> {
+        min_x: Index,
+        min_y: Index,
+        max_x: Index,
+        max_y: Index,
+        rows: Vec<Vec<V>>,
+    }
+
+    impl<V: Clone + fmt::Debug + Serialize> Serialize for DenseGrid<V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            GridRef {
+                min_x: self.bounds.min.x,
+                min_y: self.bounds.min.y,
+                max_x: self.bounds.max.x,
+                max_y: self.bounds.max.y,
+                rows: self.cells.chunks(self.width).collect(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, V: Clone + fmt::Debug + Deserialize<'de>> Deserialize<'de> for DenseGrid<V> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let g = GridOwned::<V>::deserialize(deserializer)?;
+            if g.max_x < g.min_x || g.max_y < g.min_y {
+                return Err(D::Error::custom("invalid grid bounds"));
+            }
+            let width = 1 + g.max_x.abs_diff(g.min_x) as usize;
+            let height = 1 + g.max_y.abs_diff(g.min_y) as usize;
+            if g.rows.len() != height {
+                return Err(D::Error::custom(format!(
+                    "expected {} rows, got {}",
+                    height,
+                    g.rows.len()
+                )));
+            }
+            if let Some((y, row)) = g.rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+                return Err(D::Error::custom(format!(
+                    "expected row {} to have {} cells, got {}",
+                    y,
+                    width,
+                    row.len()
+                )));
+            }
+            Ok(DenseGrid {
+                bounds: Rect::new(Point::new(g.min_x, g.min_y), Point::new(g.max_x, g.max_y)),
+                width,
+                height,
+                cells: g.rows.into_iter().flatten().collect(),
+            })
+        }
+    }
+}
+
+pub struct DisplayWith<'a, V: Clone + fmt::Debug, F: Fn(&V) -> char> {
     grid: &'a DenseGrid<V>,
-    y: Index,
+    f: F,
+}
+
+impl<'a, V: Clone + fmt::Debug, F: Fn(&V) -> char> fmt::Display for DisplayWith<'a, V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.grid.text_lines(&self.f, false) {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DenseGrid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(|c| *c).fmt(f)
+    }
+}
+
+/// Assert that two grids are equal, printing a visual diff if they are not.
+///
+/// The optional third argument maps cells to chars; without it, cells must
+/// be convertible with `char::from`.
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_grid_eq!($left, $right, |v| char::from(*v))
+    };
+    ($left:expr, $right:expr, $f:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(diff) = left.diff_with(right, $f) {
+                    panic!("assertion failed: grids are not equal\n{}", diff);
+                }
+            }
+        }
+    };
+}
+
+pub struct Rows<'a, V: Clone + std::fmt::Debug> {
+    inner: std::slice::Chunks<'a, V>,
 }
 
 impl<'a, V: Clone + std::fmt::Debug> Iterator for Rows<'a, V> {
-    type Item = Vec<V>;
+    type Item = &'a [V];
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.y > self.grid.max_y {
-            return None;
-        }
-        let val = self
-            .grid
-            .column_numbers()
-            .map(|x| {
-                let pt = Point::new(x, self.y);
-                self.grid.get(pt).unwrap()
-            })
-            .collect();
-        self.y += 1;
-        Some(val)
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, V: Clone + std::fmt::Debug> DoubleEndedIterator for Rows<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, V: Clone + std::fmt::Debug> ExactSizeIterator for Rows<'a, V> {}
+
+/// A view of a single column; iterates over the cells from top to bottom
+pub struct Column<'a, V: Clone + std::fmt::Debug> {
+    inner: std::iter::StepBy<std::slice::Iter<'a, V>>,
+}
+
+impl<'a, V: Clone + std::fmt::Debug> Iterator for Column<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+impl<'a, V: Clone + std::fmt::Debug> ExactSizeIterator for Column<'a, V> {}
+
 pub struct Columns<'a, V: Clone + std::fmt::Debug> {
     grid: &'a DenseGrid<V>,
     x: Index,
 }
 
 impl<'a, V: Clone + std::fmt::Debug> Iterator for Columns<'a, V> {
-    type Item = Vec<V>;
+    type Item = Column<'a, V>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.x > self.grid.max_x {
+        if self.x > self.grid.bounds.max.x {
             return None;
         }
-        let val = self
-            .grid
-            .row_numbers()
-            .map(|y| {
-                let pt = Point::new(self.x, y);
-                self.grid.get(pt).unwrap()
-            })
-            .collect();
+        let column = self.grid.column(self.x);
         self.x += 1;
-        Some(val)
+        Some(column)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.grid.bounds.max.x - self.x + 1).max(0) as usize;
+        (remaining, Some(remaining))
     }
 }
 
+impl<'a, V: Clone + std::fmt::Debug> ExactSizeIterator for Columns<'a, V> {}
+
 pub struct Iter<'a, V: Clone + std::fmt::Debug> {
     grid: &'a DenseGrid<V>,
-    x: Index,
-    y: Index,
+    inner: std::iter::Enumerate<std::slice::Iter<'a, V>>,
 }
 
 impl<'a, V: Clone + std::fmt::Debug> Iterator for Iter<'a, V> {
-    type Item = (Point<Index>, V);
+    type Item = (Point<Index>, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.y > self.grid.max_y {
-            return None;
-        }
-        let pt = Point::new(self.x, self.y);
-        let value = self.grid.get(pt).unwrap();
-        if self.x >= self.grid.max_x {
-            self.x = self.grid.min_x;
-            self.y += 1;
-        } else {
-            self.x += 1;
-        }
-        Some((pt, value))
+        let (i, v) = self.inner.next()?;
+        Some((point_for(self.grid.origin(), self.grid.width, i), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+impl<'a, V: Clone + std::fmt::Debug> ExactSizeIterator for Iter<'a, V> {}
+
 impl<'a, V: Clone + std::fmt::Debug> std::iter::FusedIterator for Iter<'a, V> {}
 
+pub struct IterMut<'a, V: Clone + std::fmt::Debug> {
+    origin: Point<Index>,
+    width: usize,
+    inner: std::iter::Enumerate<std::slice::IterMut<'a, V>>,
+}
+
+impl<'a, V: Clone + std::fmt::Debug> Iterator for IterMut<'a, V> {
+    type Item = (Point<Index>, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, v) = self.inner.next()?;
+        Some((point_for(self.origin, self.width, i), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, V: Clone + std::fmt::Debug> ExactSizeIterator for IterMut<'a, V> {}
+
+impl<'a, V: Clone + std::fmt::Debug> std::iter::FusedIterator for IterMut<'a, V> {}
+
 impl<V: Clone + std::fmt::Debug> std::ops::Index<Point<Index>> for DenseGrid<V> {
     type Output = V;
 
@@ -450,7 +939,7 @@ impl<V: Clone + std::fmt::Debug> std::ops::IndexMut<Point<Index>> for DenseGrid<
 
 #[cfg(test)]
 mod tests {
-    use super::{DenseGrid, Point};
+    use super::{DenseGrid, ParseError, ParseMode, Point};
 
     #[test]
     fn test_small() {
@@ -473,6 +962,18 @@ mod tests {
         assert_eq!(g[Point { x: 50, y: 50 }], 4);
     }
 
+    #[test]
+    fn test_bounds() {
+        use crate::Rect;
+
+        let bounds = Rect::new(Point::new(-1, 2), Point::new(3, 4));
+        let g = DenseGrid::new_with_bounds(bounds, 0u8);
+        assert_eq!(g.bounds(), bounds);
+        assert_eq!(g.size(), bounds.area() as usize);
+        assert!(g.contains(Point::new(3, 4)));
+        assert!(!g.contains(Point::new(3, 5)));
+    }
+
     #[test]
     fn test_columns() {
         let mut g = DenseGrid::new_with(Point { x: 0, y: 0 }, Point { x: 3, y: 3 }, 0u8);
@@ -481,7 +982,9 @@ mod tests {
         g.set(Point::new(2, 2), 3);
         g.set(Point::new(3, 3), 4);
         assert_eq!(
-            g.columns().collect::<Vec<_>>(),
+            g.columns()
+                .map(|c| c.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
             vec![
                 vec![1, 0, 0, 0],
                 vec![0, 2, 0, 0],
@@ -491,6 +994,205 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_text_round_trip() {
+        let input = "#..#\n.##.\n#..#\n";
+        let g = DenseGrid::from_input(input, |c| c == '#');
+        let rendered = g.to_string_with(|v| if *v { '#' } else { '.' });
+        assert_eq!(rendered, input);
+        let mut buf = vec![];
+        g.write_with(&mut buf, |v| if *v { '#' } else { '.' })
+            .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), input);
+        assert_eq!(DenseGrid::from_input(&rendered, |c| c == '#'), g);
+    }
+
+    #[test]
+    fn test_text_ragged() {
+        let input = "ab\nabcd\na\n";
+        let g = DenseGrid::from_input(input, |c| c);
+        assert_eq!(g.width(), 4);
+        assert_eq!(
+            g.to_string_with(|c| if *c == '\0' { ' ' } else { *c }),
+            "ab  \nabcd\na   \n"
+        );
+        assert_eq!(
+            g.to_trimmed_string_with(|c| if *c == '\0' { ' ' } else { *c }),
+            input
+        );
+        let padded = DenseGrid::from_input("a \nb \n", |c| c);
+        assert_eq!(padded.to_string(), "a \nb \n");
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let g = DenseGrid::parse_input("ab\r\ncd\r\n\n", ParseMode::Strict, |c| {
+            Ok::<_, anyhow::Error>(c)
+        })
+        .unwrap();
+        assert_eq!(g.width(), 2);
+        assert_eq!(g.height(), 2);
+        assert_eq!(g[Point::new(1, 1)], 'd');
+
+        let err = DenseGrid::parse_input("abc\nabc\nab\n", ParseMode::Strict, |c| {
+            Ok::<_, anyhow::Error>(c)
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "line 3: expected 3 cells, found 2");
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let g = DenseGrid::parse_input("ab\nabcd\n", ParseMode::Lenient { fill: '.' }, |c| {
+            Ok::<_, anyhow::Error>(c)
+        })
+        .unwrap();
+        assert_eq!(g.to_string(), "ab..\nabcd\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse =
+            |s: &str| DenseGrid::parse_input(s, ParseMode::Strict, |c| c.to_string().parse::<u8>());
+        assert_eq!(parse("").unwrap_err(), ParseError::Empty);
+        assert_eq!(parse("\n\n").unwrap_err().to_string(), "empty input");
+        assert_eq!(
+            parse("12\n3").unwrap_err(),
+            ParseError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 1
+            }
+        );
+        let err = parse("12\n3x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 2: invalid digit found in string"
+        );
+        let err: anyhow::Error = err.into();
+        assert_eq!(
+            err.root_cause().to_string(),
+            "invalid digit found in string"
+        );
+        let err = parse("12\n3x\n").unwrap_err().into_cell_error().unwrap();
+        assert_eq!(err, "x".parse::<u8>().unwrap_err());
+    }
+
+    #[test]
+    fn test_try_from_input() {
+        // the cell parser's own error type comes back untouched
+        let try_parse =
+            |s: &str| DenseGrid::try_from_input(s, |c| if c == 'x' { Err(c) } else { Ok(c) });
+        let g = try_parse("ab\nc").unwrap();
+        assert_eq!((g.width(), g.height()), (2, 2));
+        assert_eq!(try_parse("ab\nx"), Err('x'));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid grid input")]
+    fn test_try_from_input_empty() {
+        let _ = DenseGrid::try_from_input("\n", Ok::<char, ()>);
+    }
+
+    #[test]
+    fn test_parse_with_markers() {
+        let (g, markers) = DenseGrid::parse_input_with_markers(
+            "..S..\n.....\n..^..\n",
+            ParseMode::Strict,
+            &['S', '^'],
+            |c| Ok::<_, anyhow::Error>(c != '.'),
+        )
+        .unwrap();
+        assert_eq!(g.find(&true), Some(Point::new(2, 0)));
+        assert_eq!(markers[&'S'], vec![Point::new(2, 0)]);
+        assert_eq!(markers[&'^'], vec![Point::new(2, 2)]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let left = DenseGrid::from_input("ab\ncd", |c| c);
+        let mut right = left.clone();
+        assert_eq!(left.diff_with(&right, |c| *c), None);
+        crate::assert_grid_eq!(left, right);
+        right[Point::new(1, 1)] = 'x';
+        assert_eq!(
+            left.diff_with(&right, |c| *c).unwrap(),
+            "left | right | diff\nab   | ab    | ..\ncd   | cx    | .X\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "grids are not equal")]
+    fn test_assert_grid_eq_panics() {
+        let left = DenseGrid::from_input("ab", |c| c);
+        let right = DenseGrid::from_input("ba", |c| c);
+        crate::assert_grid_eq!(left, right, |c| *c);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut g = DenseGrid::new_with(Point::new(-1, 2), Point::new(1, 3), 0u8);
+        g.set(Point::new(0, 3), 5);
+        let s = serde_json::to_string(&g).unwrap();
+        assert_eq!(
+            s,
+            r#"{"min_x":-1,"min_y":2,"max_x":1,"max_y":3,"rows":[[0,0,0],[0,5,0]]}"#
+        );
+        let decoded: DenseGrid<u8> = serde_json::from_str(&s).unwrap();
+        assert_eq!(decoded, g);
+        assert_eq!(decoded.width(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_ragged() {
+        let s = r#"{"min_x":0,"min_y":0,"max_x":1,"max_y":1,"rows":[[0,0],[0]]}"#;
+        assert!(serde_json::from_str::<DenseGrid<u8>>(s).is_err());
+        let s = r#"{"min_x":0,"min_y":0,"max_x":1,"max_y":1,"rows":[[0,0]]}"#;
+        assert!(serde_json::from_str::<DenseGrid<u8>>(s).is_err());
+    }
+
+    #[test]
+    fn test_par_iter() {
+        use rayon::prelude::*;
+
+        let mut g = DenseGrid::new_with(Point::new(-2, 5), Point::new(2, 8), 0i64);
+        g.par_iter_mut().for_each(|(p, v)| *v = p.x * p.y);
+        assert_eq!(g[Point::new(-2, 5)], -10);
+        assert_eq!(g[Point::new(2, 8)], 16);
+        let points = g.par_iter().map(|(p, _)| p).collect::<Vec<_>>();
+        assert_eq!(points, g.iter().map(|(p, _)| p).collect::<Vec<_>>());
+        let sums = g
+            .par_rows()
+            .map(|(y, row)| (y, row.iter().sum::<i64>()))
+            .collect::<Vec<_>>();
+        assert_eq!(sums, vec![(5, 0), (6, 0), (7, 0), (8, 0)]);
+        let doubled = g.par_map(|_, v| v * 2);
+        assert_eq!(doubled.origin(), g.origin());
+        assert_eq!(doubled[Point::new(2, 8)], 32);
+    }
+
+    #[test]
+    fn test_borrowing_iterators() {
+        let mut g = DenseGrid::from_input("abc\ndef", |c| c);
+        assert_eq!(g.row(1), &['d', 'e', 'f']);
+        assert_eq!(
+            g.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'], &['d', 'e', 'f']]
+        );
+        assert_eq!(g.column(1).copied().collect::<String>(), "be");
+        assert_eq!(g.column(2).len(), 2);
+        assert_eq!(g.iter().nth(4), Some((Point::new(1, 1), &'e')));
+        for (p, v) in g.iter_mut() {
+            if p.y == 0 {
+                *v = v.to_ascii_uppercase();
+            }
+        }
+        g.row_mut(1)[2] = 'F';
+        assert_eq!(g.to_string(), "ABC\ndeF\n");
+    }
+
     #[test]
     fn test_dijkstra() {
         use crate::DijkstraMetric;
@@ -0,0 +1,408 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+use super::DijkstraMetric;
+use super::grid::{DenseGrid, HasEmpty};
+use super::point::Point;
+use super::vec3::Vec3;
+
+type Index = i64;
+
+/// A dense 3D grid of voxels, inclusive of both corners
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid3<V: Clone + fmt::Debug> {
+    min: Vec3<Index>,
+    max: Vec3<Index>,
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: Vec<V>,
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid3<V> {
+    pub fn new(corner: Vec3<Index>, opposite: Vec3<Index>) -> Self {
+        Self::new_with(corner, opposite, V::empty_value())
+    }
+
+    /// start a 6-connected flood-fill at the given point; fill empty
+    pub fn flood_fill(&mut self, start: Vec3<Index>, with: V) -> anyhow::Result<()> {
+        let empty = V::empty_value();
+        if !self.contains(start) {
+            anyhow::bail!("invalid start index")
+        };
+        if with == empty {
+            anyhow::bail!("don't fill with empty");
+        }
+        let mut q = VecDeque::new();
+        q.push_back(start);
+        while let Some(next) = q.pop_front() {
+            if let Some(index) = self.index_for(next)
+                && self.cells[index] == empty
+            {
+                self.cells[index] = with.clone();
+                q.extend(next.face_neighbors_array());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<V: Clone + fmt::Debug> DenseGrid3<V> {
+    pub fn new_with(corner: Vec3<Index>, opposite: Vec3<Index>, empty_value: V) -> Self {
+        let min = Vec3::new(
+            corner.x.min(opposite.x),
+            corner.y.min(opposite.y),
+            corner.z.min(opposite.z),
+        );
+        let max = Vec3::new(
+            corner.x.max(opposite.x),
+            corner.y.max(opposite.y),
+            corner.z.max(opposite.z),
+        );
+        let width = 1 + max.x.abs_diff(min.x) as usize;
+        let height = 1 + max.y.abs_diff(min.y) as usize;
+        let depth = 1 + max.z.abs_diff(min.z) as usize;
+        Self {
+            min,
+            max,
+            width,
+            height,
+            depth,
+            cells: vec![empty_value; width * height * depth],
+        }
+    }
+
+    pub fn new_with_dimensions_from<T: Clone + fmt::Debug>(
+        other: &DenseGrid3<T>,
+        empty_value: V,
+    ) -> Self {
+        Self::new_with(other.min, other.max, empty_value)
+    }
+
+    /// A grid just large enough to hold the given points, which are set to
+    /// `filled_value`; everything else is `empty_value`. None if there are no points.
+    pub fn from_points<It: IntoIterator<Item = Vec3<Index>>>(
+        points: It,
+        empty_value: V,
+        filled_value: V,
+    ) -> Option<Self> {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let first = *points.first()?;
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            (
+                Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        });
+        let mut grid = Self::new_with(min, max, empty_value);
+        for p in points {
+            grid[p] = filled_value.clone();
+        }
+        Some(grid)
+    }
+
+    /// The minimum and maximum corners, inclusive
+    pub fn bounds(&self) -> (Vec3<Index>, Vec3<Index>) {
+        (self.min, self.max)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total number of cells
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn contains(&self, coordinate: Vec3<Index>) -> bool {
+        (self.min.x..=self.max.x).contains(&coordinate.x)
+            && (self.min.y..=self.max.y).contains(&coordinate.y)
+            && (self.min.z..=self.max.z).contains(&coordinate.z)
+    }
+
+    fn index_for(&self, coordinate: Vec3<Index>) -> Option<usize> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+        let local = coordinate - self.min;
+        Some((local.z as usize * self.height + local.y as usize) * self.width + local.x as usize)
+    }
+
+    fn point_for(&self, index: usize) -> Vec3<Index> {
+        let x = index % self.width;
+        let y = (index / self.width) % self.height;
+        let z = index / (self.width * self.height);
+        self.min + Vec3::new(x as Index, y as Index, z as Index)
+    }
+
+    pub fn get(&self, coordinate: Vec3<Index>) -> Option<V> {
+        self.index_for(coordinate).map(|i| self.cells[i].clone())
+    }
+
+    pub fn get_mut(&mut self, coordinate: Vec3<Index>) -> Option<&mut V> {
+        let index = self.index_for(coordinate)?;
+        self.cells.get_mut(index)
+    }
+
+    pub fn set(&mut self, coordinate: Vec3<Index>, value: V) -> Option<()> {
+        let index = self.index_for(coordinate)?;
+        self.cells[index] = value;
+        Some(())
+    }
+
+    /// Iterate over all cells, x varying fastest and z slowest
+    pub fn iter(&self) -> impl Iterator<Item = (Vec3<Index>, &V)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (self.point_for(i), v))
+    }
+
+    /// The x/y plane at the given z, or None if z is out of bounds
+    pub fn layer(&self, z: Index) -> Option<DenseGrid<V>> {
+        if !(self.min.z..=self.max.z).contains(&z) {
+            return None;
+        }
+        let mut out = DenseGrid::new_with(
+            Point::new(self.min.x, self.min.y),
+            Point::new(self.max.x, self.max.y),
+            self.cells[0].clone(),
+        );
+        let start = (z - self.min.z) as usize * self.width * self.height;
+        let cells = &self.cells[start..start + self.width * self.height];
+        for (row, cells) in out.rows_mut().zip(cells.chunks(self.width)) {
+            row.clone_from_slice(cells);
+        }
+        Some(out)
+    }
+
+    /// Every x/y plane, from lowest z to highest
+    pub fn layers(&self) -> impl Iterator<Item = (Index, DenseGrid<V>)> + '_ {
+        (self.min.z..=self.max.z).map(|z| (z, self.layer(z).expect("z in bounds")))
+    }
+
+    /// The number of voxel faces between a solid voxel and a non-solid one
+    /// (or the outside of the grid)
+    pub fn surface_area<F: Fn(&V) -> bool>(&self, is_solid: F) -> usize {
+        self.iter()
+            .filter(|(_, v)| is_solid(v))
+            .map(|(p, _)| {
+                p.face_neighbors_array()
+                    .into_iter()
+                    .filter(|n| !self.get(*n).is_some_and(|v| is_solid(&v)))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Same as [`DenseGrid3::surface_area`], but only counting faces which
+    /// can be reached from outside the grid; enclosed air pockets don't count
+    pub fn exterior_surface_area<F: Fn(&V) -> bool>(&self, is_solid: F) -> usize {
+        // flood the air in a grid one voxel larger on every side, so the
+        // outside is connected all the way around
+        let one = Vec3::new(1, 1, 1);
+        let mut outside = DenseGrid3::new_with(self.min - one, self.max + one, false);
+        let mut q = VecDeque::from([self.min - one]);
+        let mut faces = 0;
+        while let Some(p) = q.pop_front() {
+            if outside[p] {
+                continue;
+            }
+            outside[p] = true;
+            for n in p.face_neighbors_array() {
+                if !outside.contains(n) || outside[n] {
+                    continue;
+                }
+                if self.get(n).is_some_and(|v| is_solid(&v)) {
+                    faces += 1;
+                } else {
+                    q.push_back(n);
+                }
+            }
+        }
+        faces
+    }
+
+    /// Breadth-first search over 6-connected neighbors, returning the number
+    /// of steps from `start` to each reachable cell
+    pub fn bfs<TF>(
+        &self,
+        start: Vec3<Index>,
+        traversible: TF,
+    ) -> anyhow::Result<DenseGrid3<Option<usize>>>
+    where
+        TF: Fn(&Self, Vec3<Index>) -> bool,
+    {
+        if !self.contains(start) {
+            anyhow::bail!("start point not contained in map");
+        }
+        let mut distances = DenseGrid3::new_with_dimensions_from(self, None);
+        distances[start] = Some(0);
+        let mut q = VecDeque::from([(start, 0)]);
+        while let Some((point, steps)) = q.pop_front() {
+            for neighbor in point.face_neighbors_array() {
+                if distances.get(neighbor) == Some(None) && traversible(self, neighbor) {
+                    distances[neighbor] = Some(steps + 1);
+                    q.push_back((neighbor, steps + 1));
+                }
+            }
+        }
+        Ok(distances)
+    }
+
+    /// Dijkstra's algorithm over 6-connected neighbors; see [`DenseGrid::dijkstra`]
+    #[allow(clippy::type_complexity)]
+    pub fn dijkstra<MV, TF, F>(
+        &self,
+        start: Vec3<Index>,
+        traversible: TF,
+        cost: F,
+    ) -> anyhow::Result<(
+        DenseGrid3<DijkstraMetric<MV>>,
+        DenseGrid3<Option<Vec3<Index>>>,
+    )>
+    where
+        MV: std::fmt::Debug
+            + Clone
+            + Copy
+            + PartialEq
+            + Eq
+            + PartialOrd
+            + Ord
+            + num_traits::Zero
+            + std::ops::Add,
+        F: Fn(&Self, Vec3<Index>, Vec3<Index>) -> MV,
+        TF: Fn(&Self, Vec3<Index>) -> bool,
+    {
+        if !self.contains(start) {
+            anyhow::bail!("start point not contained in map");
+        }
+        let mut distances = DenseGrid3::new_with_dimensions_from(self, DijkstraMetric::Infinite);
+        distances[start] = DijkstraMetric::Finite(MV::zero());
+        let mut preds = DenseGrid3::new_with_dimensions_from(self, None);
+        let mut visited = DenseGrid3::new_with_dimensions_from(self, false);
+        let mut unvisited = BinaryHeap::new();
+        unvisited.push((Reverse(MV::zero()), start));
+        while let Some((Reverse(current), point)) = unvisited.pop() {
+            if visited[point] {
+                continue;
+            }
+            visited[point] = true;
+            for neighbor in point.face_neighbors_array() {
+                if !self.contains(neighbor) || visited[neighbor] || !traversible(self, neighbor) {
+                    continue;
+                }
+                let next = current + cost(self, point, neighbor);
+                if DijkstraMetric::Finite(next) < distances[neighbor] {
+                    distances[neighbor] = DijkstraMetric::Finite(next);
+                    preds[neighbor] = Some(point);
+                    unvisited.push((Reverse(next), neighbor));
+                }
+            }
+        }
+        Ok((distances, preds))
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::Index<Vec3<Index>> for DenseGrid3<V> {
+    type Output = V;
+
+    fn index(&self, coordinate: Vec3<Index>) -> &Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        &self.cells[index]
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::IndexMut<Vec3<Index>> for DenseGrid3<V> {
+    fn index_mut(&mut self, coordinate: Vec3<Index>) -> &mut Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        &mut self.cells[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DenseGrid3;
+    use crate::dijkstra_metric::DijkstraMetric;
+    use crate::vec3::Vec3;
+
+    fn cube_with_hole() -> DenseGrid3<bool> {
+        // a hollow 3x3x3 cube, with the center empty
+        let points = (0..27)
+            .map(|i| Vec3::new(i % 3, (i / 3) % 3, i / 9))
+            .filter(|p| *p != Vec3::new(1, 1, 1));
+        DenseGrid3::from_points(points, false, true).unwrap()
+    }
+
+    #[test]
+    fn test_bounds_and_access() {
+        let mut g = DenseGrid3::<u8>::new(Vec3::new(2, -1, 5), Vec3::new(0, 1, 3));
+        assert_eq!(g.bounds(), (Vec3::new(0, -1, 3), Vec3::new(2, 1, 5)));
+        assert_eq!((g.width(), g.height(), g.depth(), g.size()), (3, 3, 3, 27));
+        assert_eq!(g.set(Vec3::new(1, 0, 4), 7), Some(()));
+        assert_eq!(g.set(Vec3::new(1, 0, 6), 7), None);
+        assert_eq!(g.get(Vec3::new(1, 0, 4)), Some(7));
+        assert_eq!(g.get(Vec3::new(3, 0, 4)), None);
+        *g.get_mut(Vec3::new(0, -1, 3)).unwrap() = 2;
+        let nonzero = g.iter().filter(|(_, v)| **v != 0).collect::<Vec<_>>();
+        assert_eq!(
+            nonzero,
+            vec![(Vec3::new(0, -1, 3), &2), (Vec3::new(1, 0, 4), &7)]
+        );
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let g = cube_with_hole();
+        assert_eq!(g.surface_area(|v| *v), 54 + 6);
+        assert_eq!(g.exterior_surface_area(|v| *v), 54);
+        let single = DenseGrid3::from_points([Vec3::new(0, 0, 0)], false, true).unwrap();
+        assert_eq!(single.surface_area(|v| *v), 6);
+        assert_eq!(single.exterior_surface_area(|v| *v), 6);
+    }
+
+    #[test]
+    fn test_flood_fill_and_layers() {
+        let mut g = DenseGrid3::new(Vec3::new(0, 0, 0), Vec3::new(2, 2, 1));
+        for p in [Vec3::new(1, 0, 0), Vec3::new(1, 1, 0), Vec3::new(1, 2, 0)] {
+            g[p] = '#';
+        }
+        g.flood_fill(Vec3::new(0, 0, 0), 'o').unwrap();
+        // the wall only spans z=0, so the fill goes around it through z=1
+        assert_eq!(g.layer(0).unwrap().to_string(), "o#o\no#o\no#o\n");
+        assert_eq!(g.layer(1).unwrap().to_string(), "ooo\nooo\nooo\n");
+        assert!(g.layer(2).is_none());
+        assert_eq!(g.layers().map(|(z, _)| z).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(g.flood_fill(Vec3::new(5, 0, 0), 'o').is_err());
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let g = cube_with_hole();
+        let open = |g: &DenseGrid3<bool>, p| !g[p];
+        let distances = g.bfs(Vec3::new(1, 1, 1), open).unwrap();
+        assert_eq!(distances[Vec3::new(1, 1, 1)], Some(0));
+        assert_eq!(distances[Vec3::new(0, 0, 0)], None);
+
+        let g = DenseGrid3::<bool>::new(Vec3::new(0, 0, 0), Vec3::new(2, 2, 2));
+        let distances = g.bfs(Vec3::new(0, 0, 0), |_, _| true).unwrap();
+        assert_eq!(distances[Vec3::new(2, 2, 2)], Some(6));
+        let (costs, preds) = g
+            .dijkstra(Vec3::new(0, 0, 0), |_, _| true, |_, _, to| to.z * 10 + 1)
+            .unwrap();
+        assert_eq!(costs[Vec3::new(2, 2, 0)], DijkstraMetric::Finite(4));
+        assert_eq!(costs[Vec3::new(0, 0, 2)], DijkstraMetric::Finite(32));
+        assert_eq!(preds[Vec3::new(0, 0, 0)], None);
+        assert!(preds[Vec3::new(2, 2, 2)].is_some());
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use super::grid::HasEmpty;
+use super::point_n::PointN;
+
+type Index = i64;
+
+/// A dense grid in `D` dimensions, inclusive of both corners. Cells are
+/// stored with axis 0 varying fastest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGridN<V: Clone + fmt::Debug, const D: usize> {
+    min: PointN<Index, D>,
+    max: PointN<Index, D>,
+    /// the number of cells along each axis
+    dims: [usize; D],
+    cells: Vec<V>,
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty, const D: usize> DenseGridN<V, D> {
+    pub fn new(corner: PointN<Index, D>, opposite: PointN<Index, D>) -> Self {
+        Self::new_with(corner, opposite, V::empty_value())
+    }
+
+    /// start a flood-fill over face neighbors at the given point; fill empty
+    pub fn flood_fill(&mut self, start: PointN<Index, D>, with: V) -> anyhow::Result<()> {
+        let empty = V::empty_value();
+        if !self.contains(start) {
+            anyhow::bail!("invalid start index")
+        };
+        if with == empty {
+            anyhow::bail!("don't fill with empty");
+        }
+        let mut q = VecDeque::new();
+        q.push_back(start);
+        while let Some(next) = q.pop_front() {
+            if let Some(index) = self.index_for(next)
+                && self.cells[index] == empty
+            {
+                self.cells[index] = with.clone();
+                q.extend(next.face_neighbors());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<V: Clone + fmt::Debug, const D: usize> DenseGridN<V, D> {
+    pub fn new_with(corner: PointN<Index, D>, opposite: PointN<Index, D>, empty_value: V) -> Self {
+        let min = corner.component_min(opposite);
+        let max = corner.component_max(opposite);
+        let dims = std::array::from_fn(|a| 1 + max[a].abs_diff(min[a]) as usize);
+        Self {
+            min,
+            max,
+            dims,
+            cells: vec![empty_value; dims.iter().product()],
+        }
+    }
+
+    pub fn new_with_dimensions_from<T: Clone + fmt::Debug>(
+        other: &DenseGridN<T, D>,
+        empty_value: V,
+    ) -> Self {
+        Self::new_with(other.min, other.max, empty_value)
+    }
+
+    /// A grid just large enough to hold the given points, which are set to
+    /// `filled_value`; everything else is `empty_value`. None if there are no points.
+    pub fn from_points<It: IntoIterator<Item = PointN<Index, D>>>(
+        points: It,
+        empty_value: V,
+        filled_value: V,
+    ) -> Option<Self> {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let first = *points.first()?;
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            (min.component_min(*p), max.component_max(*p))
+        });
+        let mut grid = Self::new_with(min, max, empty_value);
+        for p in points {
+            grid[p] = filled_value.clone();
+        }
+        Some(grid)
+    }
+
+    /// The minimum and maximum corners, inclusive
+    pub fn bounds(&self) -> (PointN<Index, D>, PointN<Index, D>) {
+        (self.min, self.max)
+    }
+
+    /// The number of cells along each axis
+    pub fn dimensions(&self) -> [usize; D] {
+        self.dims
+    }
+
+    /// Total number of cells
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn contains(&self, coordinate: PointN<Index, D>) -> bool {
+        (0..D).all(|a| (self.min[a]..=self.max[a]).contains(&coordinate[a]))
+    }
+
+    fn index_for(&self, coordinate: PointN<Index, D>) -> Option<usize> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+        let local = coordinate - self.min;
+        Some(
+            (0..D)
+                .rev()
+                .fold(0, |index, a| index * self.dims[a] + local[a] as usize),
+        )
+    }
+
+    fn point_for(&self, mut index: usize) -> PointN<Index, D> {
+        self.min
+            + PointN::from_fn(|a| {
+                let v = index % self.dims[a];
+                index /= self.dims[a];
+                v as Index
+            })
+    }
+
+    pub fn get(&self, coordinate: PointN<Index, D>) -> Option<V> {
+        self.index_for(coordinate).map(|i| self.cells[i].clone())
+    }
+
+    pub fn get_mut(&mut self, coordinate: PointN<Index, D>) -> Option<&mut V> {
+        let index = self.index_for(coordinate)?;
+        self.cells.get_mut(index)
+    }
+
+    pub fn set(&mut self, coordinate: PointN<Index, D>, value: V) -> Option<()> {
+        let index = self.index_for(coordinate)?;
+        self.cells[index] = value;
+        Some(())
+    }
+
+    /// Iterate over all cells, axis 0 varying fastest
+    pub fn iter(&self) -> impl Iterator<Item = (PointN<Index, D>, &V)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (self.point_for(i), v))
+    }
+
+    /// The in-bounds points one step away along a single axis
+    pub fn face_neighbors(&self, coordinate: PointN<Index, D>) -> Vec<PointN<Index, D>> {
+        let mut out = coordinate.face_neighbors();
+        out.retain(|p| self.contains(*p));
+        out
+    }
+
+    /// The in-bounds points differing by at most one along every axis
+    pub fn all_neighbors(&self, coordinate: PointN<Index, D>) -> Vec<PointN<Index, D>> {
+        let mut out = coordinate.all_neighbors();
+        out.retain(|p| self.contains(*p));
+        out
+    }
+
+    /// A copy of the grid with `by` extra cells of `fill` on every side, e.g.
+    /// to give a cellular automaton room to grow before each step
+    pub fn grown(&self, by: Index, fill: V) -> Self {
+        let by = PointN::from_fn(|_| by);
+        let mut out = Self::new_with(self.min - by, self.max + by, fill);
+        for (p, v) in self.iter() {
+            out[p] = v.clone();
+        }
+        out
+    }
+}
+
+impl<V: Clone + fmt::Debug, const D: usize> std::ops::Index<PointN<Index, D>> for DenseGridN<V, D> {
+    type Output = V;
+
+    fn index(&self, coordinate: PointN<Index, D>) -> &Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        &self.cells[index]
+    }
+}
+
+impl<V: Clone + fmt::Debug, const D: usize> std::ops::IndexMut<PointN<Index, D>>
+    for DenseGridN<V, D>
+{
+    fn index_mut(&mut self, coordinate: PointN<Index, D>) -> &mut Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        &mut self.cells[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DenseGridN;
+    use crate::point_n::PointN;
+
+    #[test]
+    fn test_bounds_and_access() {
+        let mut g = DenseGridN::<u8, 4>::new(PointN::new([2, -1, 5, 0]), PointN::new([0, 1, 3, 1]));
+        assert_eq!(
+            g.bounds(),
+            (PointN::new([0, -1, 3, 0]), PointN::new([2, 1, 5, 1]))
+        );
+        assert_eq!((g.dimensions(), g.size()), ([3, 3, 3, 2], 54));
+        assert_eq!(g.set(PointN::new([1, 0, 4, 1]), 7), Some(()));
+        assert_eq!(g.set(PointN::new([1, 0, 4, 2]), 7), None);
+        assert_eq!(g.get(PointN::new([1, 0, 4, 1])), Some(7));
+        assert_eq!(g.get(PointN::new([3, 0, 4, 1])), None);
+        *g.get_mut(PointN::new([0, -1, 3, 0])).unwrap() = 2;
+        let nonzero = g.iter().filter(|(_, v)| **v != 0).collect::<Vec<_>>();
+        assert_eq!(
+            nonzero,
+            vec![
+                (PointN::new([0, -1, 3, 0]), &2),
+                (PointN::new([1, 0, 4, 1]), &7)
+            ]
+        );
+        assert!(g.iter().all(|(p, v)| g[p] == *v));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let g = DenseGridN::<u8, 4>::new(PointN::origin(), PointN::new([2; 4]));
+        assert_eq!(g.all_neighbors(PointN::new([1; 4])).len(), 80);
+        assert_eq!(g.face_neighbors(PointN::new([1; 4])).len(), 8);
+        assert_eq!(g.all_neighbors(PointN::origin()).len(), 15);
+        assert_eq!(g.face_neighbors(PointN::origin()).len(), 4);
+    }
+
+    #[test]
+    fn test_automaton_step() {
+        // one step of a 4D Game of Life on a 3-cell line: the ends die, the
+        // middle survives, and everything else next to the middle has all
+        // three live cells as neighbors and is born
+        let g = DenseGridN::from_points((0..3).map(|x| PointN::new([x, 0, 0, 0])), false, true)
+            .unwrap()
+            .grown(1, false);
+        let mut next = DenseGridN::new_with_dimensions_from(&g, false);
+        for (p, alive) in g.iter() {
+            let n = g.all_neighbors(p).into_iter().filter(|n| g[*n]).count();
+            next[p] = n == 3 || (*alive && n == 2);
+        }
+        assert_eq!(next.iter().filter(|(_, v)| **v).count(), 27);
+        assert!(next[PointN::new([1, 1, 1, 1])]);
+        assert!(!next[PointN::new([0, 0, 0, 0])]);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let mut g = DenseGridN::<char, 2>::new(PointN::origin(), PointN::new([2, 2]));
+        for y in 0..3 {
+            g[PointN::new([1, y])] = '#';
+        }
+        g.flood_fill(PointN::origin(), 'o').unwrap();
+        assert_eq!(g[PointN::new([0, 2])], 'o');
+        assert_eq!(g[PointN::new([2, 0])], char::default());
+        assert!(g.flood_fill(PointN::new([5, 0]), 'o').is_err());
+    }
+}
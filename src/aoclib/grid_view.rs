@@ -0,0 +1,289 @@
+use std::fmt;
+
+use super::grid::DenseGrid;
+use super::point::Point;
+use super::rect::Rect;
+
+type Index = i64;
+
+/// A borrowed rectangular window into a [`DenseGrid`].
+///
+/// Views have their own coordinate system: the upper-left cell of the window
+/// is `(0, 0)`, regardless of where it sits in the underlying grid.
+#[derive(Debug)]
+pub struct GridView<'a, V: Clone + fmt::Debug> {
+    grid: &'a DenseGrid<V>,
+    origin: Point<Index>,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, V: Clone + fmt::Debug> Clone for GridView<'a, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, V: Clone + fmt::Debug> Copy for GridView<'a, V> {}
+
+/// A mutably-borrowed rectangular window into a [`DenseGrid`]; see [`GridView`]
+#[derive(Debug)]
+pub struct GridViewMut<'a, V: Clone + fmt::Debug> {
+    grid: &'a mut DenseGrid<V>,
+    origin: Point<Index>,
+    width: usize,
+    height: usize,
+}
+
+fn window_fits<V: Clone + fmt::Debug>(
+    grid: &DenseGrid<V>,
+    origin: Point<Index>,
+    width: usize,
+    height: usize,
+) -> bool {
+    width > 0
+        && height > 0
+        && grid.contains(origin)
+        && grid.contains(origin + Point::new(width as Index - 1, height as Index - 1))
+}
+
+impl<V: Clone + fmt::Debug> DenseGrid<V> {
+    /// Borrow the `width` x `height` window whose upper-left corner is at
+    /// `origin`. Returns None if the window does not fit inside the grid.
+    pub fn view(
+        &self,
+        origin: Point<Index>,
+        width: usize,
+        height: usize,
+    ) -> Option<GridView<'_, V>> {
+        if !window_fits(self, origin, width, height) {
+            return None;
+        }
+        Some(GridView {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    pub fn view_mut(
+        &mut self,
+        origin: Point<Index>,
+        width: usize,
+        height: usize,
+    ) -> Option<GridViewMut<'_, V>> {
+        if !window_fits(self, origin, width, height) {
+            return None;
+        }
+        Some(GridViewMut {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    /// Iterate over every `width` x `height` window in the grid, in row-major
+    /// order of their upper-left corners
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = GridView<'_, V>> {
+        let Rect { min, max } = self.bounds();
+        let max_x = max.x - width as Index + 1;
+        let max_y = max.y - height as Index + 1;
+        (min.y..=max_y)
+            .flat_map(move |y| (min.x..=max_x).map(move |x| Point::new(x, y)))
+            .filter_map(move |origin| self.view(origin, width, height))
+    }
+}
+
+macro_rules! view_common {
+    () => {
+        /// The upper-left corner of this view, in the coordinates of the underlying grid
+        pub fn origin(&self) -> Point<Index> {
+            self.origin
+        }
+
+        pub fn width(&self) -> usize {
+            self.width
+        }
+
+        pub fn height(&self) -> usize {
+            self.height
+        }
+
+        pub fn size(&self) -> usize {
+            self.width * self.height
+        }
+
+        /// Is the given view-local coordinate inside this view?
+        pub fn contains(&self, coordinate: Point<Index>) -> bool {
+            coordinate.x >= 0
+                && coordinate.y >= 0
+                && coordinate.x < self.width as Index
+                && coordinate.y < self.height as Index
+        }
+
+        /// Get a value by view-local coordinate. Returns None if the
+        /// coordinate is outside the view.
+        pub fn get(&self, coordinate: Point<Index>) -> Option<&V> {
+            if !self.contains(coordinate) {
+                return None;
+            }
+            Some(&self.grid[self.origin + coordinate])
+        }
+
+        /// Iterate over the rows of this view as slices of the underlying grid
+        pub fn rows(&self) -> impl Iterator<Item = &[V]> {
+            let start = (self.origin.x - self.grid.origin().x) as usize;
+            let end = start + self.width;
+            (self.origin.y..self.origin.y + self.height as Index)
+                .map(move |y| &self.grid.row(y)[start..end])
+        }
+
+        /// Iterate over all cells in the view with their view-local coordinates
+        pub fn iter(&self) -> impl Iterator<Item = (Point<Index>, &V)> {
+            self.rows().enumerate().flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(x, v)| (Point::new(x as Index, y as Index), v))
+            })
+        }
+
+        /// Copy the contents of this view into a new grid with its upper-left
+        /// corner at `(0, 0)`
+        pub fn to_grid(&self) -> DenseGrid<V> {
+            let mut g = DenseGrid::new_with(
+                Point::new(0, 0),
+                Point::new(self.width as Index - 1, self.height as Index - 1),
+                self.grid[self.origin].clone(),
+            );
+            for (p, v) in self.iter() {
+                g[p] = v.clone();
+            }
+            g
+        }
+    };
+}
+
+impl<'a, V: Clone + fmt::Debug> GridView<'a, V> {
+    view_common!();
+}
+
+impl<'a, V: Clone + fmt::Debug> GridViewMut<'a, V> {
+    view_common!();
+
+    pub fn as_view(&self) -> GridView<'_, V> {
+        GridView {
+            grid: self.grid,
+            origin: self.origin,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn get_mut(&mut self, coordinate: Point<Index>) -> Option<&mut V> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+        self.grid.get_mut(self.origin + coordinate)
+    }
+
+    /// Set a value by view-local coordinate. Returns None if the coordinate
+    /// is outside the view.
+    pub fn set(&mut self, coordinate: Point<Index>, value: V) -> Option<()> {
+        *self.get_mut(coordinate)? = value;
+        Some(())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point<Index>, &mut V)> {
+        let start = (self.origin.x - self.grid.origin().x) as usize;
+        let end = start + self.width;
+        let skip = (self.origin.y - self.grid.origin().y) as usize;
+        self.grid
+            .rows_mut()
+            .skip(skip)
+            .take(self.height)
+            .enumerate()
+            .flat_map(move |(y, row)| {
+                row[start..end]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(x, v)| (Point::new(x as Index, y as Index), v))
+            })
+    }
+
+    pub fn fill(&mut self, value: V) {
+        for (_, v) in self.iter_mut() {
+            *v = value.clone();
+        }
+    }
+}
+
+impl<'a, V: Clone + fmt::Debug> std::ops::Index<Point<Index>> for GridView<'a, V> {
+    type Output = V;
+
+    fn index(&self, coordinate: Point<Index>) -> &Self::Output {
+        self.get(coordinate).unwrap()
+    }
+}
+
+impl<'a, V: Clone + fmt::Debug> std::ops::Index<Point<Index>> for GridViewMut<'a, V> {
+    type Output = V;
+
+    fn index(&self, coordinate: Point<Index>) -> &Self::Output {
+        self.get(coordinate).unwrap()
+    }
+}
+
+impl<'a, V: Clone + fmt::Debug> std::ops::IndexMut<Point<Index>> for GridViewMut<'a, V> {
+    fn index_mut(&mut self, coordinate: Point<Index>) -> &mut Self::Output {
+        self.get_mut(coordinate).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DenseGrid, Point};
+
+    #[test]
+    fn test_view() {
+        let g = DenseGrid::from_input("abcd\nefgh\nijkl", |c| c);
+        assert!(g.view(Point::new(2, 1), 3, 1).is_none());
+        let v = g.view(Point::new(1, 1), 2, 2).unwrap();
+        assert_eq!(v.origin(), Point::new(1, 1));
+        assert_eq!(v[Point::new(0, 0)], 'f');
+        assert_eq!(v.get(Point::new(2, 0)), None);
+        assert_eq!(v.rows().collect::<Vec<_>>(), vec![&['f', 'g'], &['j', 'k']]);
+        assert_eq!(v.to_grid().to_string(), "fg\njk\n");
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut g = DenseGrid::new_with(Point::new(-2, -2), Point::new(2, 2), '.');
+        let mut v = g.view_mut(Point::new(-1, -1), 3, 3).unwrap();
+        v.fill('#');
+        v[Point::new(1, 1)] = 'o';
+        assert_eq!(v.as_view().iter().filter(|(_, c)| **c == '#').count(), 8);
+        assert_eq!(g.to_string(), ".....\n.###.\n.#o#.\n.###.\n.....\n");
+    }
+
+    #[test]
+    fn test_windows() {
+        let g = DenseGrid::from_input("123\n456\n789", |c| c.to_digit(10).unwrap());
+        let sums = g
+            .windows(2, 2)
+            .map(|w| (w.origin(), w.iter().map(|(_, v)| v).sum::<u32>()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sums,
+            vec![
+                (Point::new(0, 0), 12),
+                (Point::new(1, 0), 16),
+                (Point::new(0, 1), 24),
+                (Point::new(1, 1), 28)
+            ]
+        );
+        assert_eq!(g.windows(3, 3).count(), 1);
+        assert_eq!(g.windows(4, 1).count(), 0);
+    }
+}
@@ -0,0 +1,532 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+use super::DijkstraMetric;
+use super::grid::{DenseGrid, HasEmpty};
+use super::point::Point;
+
+type Index = i64;
+
+/// A hexagon in axial coordinates, for "pointy-top" hexagons: `q` increases
+/// to the east and `r` increases to the south-east. The implied third cube
+/// coordinate is `s = -q - r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hex {
+    pub q: Index,
+    pub r: Index,
+}
+
+impl Hex {
+    /// The six unit steps, starting east and going counter-clockwise
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex::new(1, 0),
+        Hex::new(1, -1),
+        Hex::new(0, -1),
+        Hex::new(-1, 0),
+        Hex::new(-1, 1),
+        Hex::new(0, 1),
+    ];
+
+    pub const fn new(q: Index, r: Index) -> Self {
+        Self { q, r }
+    }
+
+    /// Build from cube coordinates, which must sum to zero
+    pub fn from_cube(q: Index, r: Index, s: Index) -> Option<Self> {
+        (q + r + s == 0).then_some(Self::new(q, r))
+    }
+
+    pub fn s(&self) -> Index {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (Index, Index, Index) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The six adjacent hexagons, in the same order as [`Hex::DIRECTIONS`]
+    pub fn neighbors(&self) -> [Hex; 6] {
+        Self::DIRECTIONS.map(|d| *self + d)
+    }
+
+    /// The number of steps between the two hexagons
+    pub fn distance(&self, other: Hex) -> Index {
+        let d = *self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    /// Every hexagon exactly `radius` steps away, going counter-clockwise
+    /// from the one `radius` steps south-west (`DIRECTIONS[4]`)
+    pub fn ring(&self, radius: Index) -> Vec<Hex> {
+        if radius <= 0 {
+            return vec![*self];
+        }
+        let mut out = Vec::with_capacity(6 * radius as usize);
+        let mut current = *self + Self::DIRECTIONS[4] * radius;
+        for direction in Self::DIRECTIONS {
+            for _ in 0..radius {
+                out.push(current);
+                current += direction;
+            }
+        }
+        out
+    }
+
+    /// Every hexagon within `radius` steps, ring by ring outward from this one
+    pub fn spiral(&self, radius: Index) -> Vec<Hex> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Rotate 60° clockwise about the origin
+    pub fn rotate_cw(&self) -> Self {
+        Self::new(-self.r, -self.s())
+    }
+
+    /// Rotate 60° counter-clockwise about the origin
+    pub fn rotate_ccw(&self) -> Self {
+        Self::new(-self.s(), -self.q)
+    }
+
+    /// Rotate about `center` by `steps` sixths of a turn (positive is clockwise)
+    pub fn rotate_about(&self, center: Hex, steps: i32) -> Self {
+        let mut offset = *self - center;
+        for _ in 0..steps.rem_euclid(6) {
+            offset = offset.rotate_cw();
+        }
+        center + offset
+    }
+
+    /// The center of this hexagon in pixel space (y down), for hexagons with
+    /// the given distance from center to corner
+    pub fn to_pixel(&self, size: f64) -> (f64, f64) {
+        let sqrt3 = 3f64.sqrt();
+        let x = size * (sqrt3 * self.q as f64 + sqrt3 / 2.0 * self.r as f64);
+        let y = size * (1.5 * self.r as f64);
+        (x, y)
+    }
+
+    /// The hexagon containing the given pixel; the inverse of [`Hex::to_pixel`]
+    pub fn from_pixel(x: f64, y: f64, size: f64) -> Self {
+        let q = (3f64.sqrt() / 3.0 * x - y / 3.0) / size;
+        let r = (2.0 / 3.0 * y) / size;
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        // fix up whichever coordinate was rounded the furthest
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Self::new(rq as Index, rr as Index)
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}, {}>", self.q, self.r)
+    }
+}
+
+impl std::ops::Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl std::ops::Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl std::ops::Mul<Index> for Hex {
+    type Output = Self;
+
+    fn mul(self, other: Index) -> Self {
+        Self::new(self.q * other, self.r * other)
+    }
+}
+
+impl std::ops::Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+impl std::ops::AddAssign for Hex {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::SubAssign for Hex {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// Dense storage for a region of hexagons: either a parallelogram in axial
+/// coordinates, or a hexagon-shaped region around a center
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexGrid<V: Clone + fmt::Debug> {
+    /// indexed by `(q, r)`
+    cells: DenseGrid<V>,
+    /// (center, radius) if only hexagons within `radius` of `center` are included
+    hexagon: Option<(Hex, Index)>,
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty> HexGrid<V> {
+    /// start flood-fill at the given hexagon; fill empty
+    pub fn flood_fill(&mut self, start: Hex, with: V) -> anyhow::Result<()> {
+        let empty = V::empty_value();
+        if !self.contains(start) {
+            anyhow::bail!("invalid start index")
+        };
+        if with == empty {
+            anyhow::bail!("don't fill with empty");
+        }
+        let mut q = VecDeque::from([start]);
+        while let Some(next) = q.pop_front() {
+            if let Some(cell) = self.get_mut(next)
+                && *cell == empty
+            {
+                *cell = with.clone();
+                q.extend(next.neighbors());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<V: Clone + fmt::Debug> HexGrid<V> {
+    /// Every hexagon whose `q` and `r` are between those of the two corners
+    pub fn new_with(corner: Hex, opposite: Hex, empty_value: V) -> Self {
+        Self {
+            cells: DenseGrid::new_with(
+                Point::new(corner.q, corner.r),
+                Point::new(opposite.q, opposite.r),
+                empty_value,
+            ),
+            hexagon: None,
+        }
+    }
+
+    /// Every hexagon within `radius` steps of `center`, or None if the radius
+    /// is negative
+    pub fn new_hexagon(center: Hex, radius: Index, empty_value: V) -> Option<Self> {
+        if radius < 0 {
+            return None;
+        }
+        let offset = Hex::new(radius, radius);
+        let mut grid = Self::new_with(center - offset, center + offset, empty_value);
+        grid.hexagon = Some((center, radius));
+        Some(grid)
+    }
+
+    pub fn new_with_dimensions_from<T: Clone + fmt::Debug>(
+        other: &HexGrid<T>,
+        empty_value: V,
+    ) -> Self {
+        Self {
+            cells: DenseGrid::new_with_dimensions_from(&other.cells, empty_value),
+            hexagon: other.hexagon,
+        }
+    }
+
+    fn point(h: Hex) -> Point<Index> {
+        Point::new(h.q, h.r)
+    }
+
+    pub fn contains(&self, h: Hex) -> bool {
+        self.cells.contains(Self::point(h))
+            && self
+                .hexagon
+                .is_none_or(|(center, radius)| center.distance(h) <= radius)
+    }
+
+    /// The number of hexagons in the grid
+    pub fn size(&self) -> usize {
+        match self.hexagon {
+            Some((_, radius)) => (3 * radius * (radius + 1) + 1) as usize,
+            None => self.cells.size(),
+        }
+    }
+
+    pub fn get(&self, h: Hex) -> Option<V> {
+        if self.contains(h) {
+            self.cells.get(Self::point(h))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, h: Hex) -> Option<&mut V> {
+        if self.contains(h) {
+            self.cells.get_mut(Self::point(h))
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, h: Hex, value: V) -> Option<()> {
+        *self.get_mut(h)? = value;
+        Some(())
+    }
+
+    /// Iterate over all hexagons in the grid, ordered by `r` and then `q`
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, &V)> {
+        self.cells
+            .iter()
+            .map(|(p, v)| (Hex::new(p.x, p.y), v))
+            .filter(|(h, _)| self.contains(*h))
+    }
+
+    /// Breadth-first search, returning the number of steps from `start` to
+    /// each reachable hexagon
+    pub fn bfs<TF>(&self, start: Hex, traversible: TF) -> anyhow::Result<HexGrid<Option<usize>>>
+    where
+        TF: Fn(&Self, Hex) -> bool,
+    {
+        if !self.contains(start) {
+            anyhow::bail!("start point not contained in map");
+        }
+        let mut distances = HexGrid::new_with_dimensions_from(self, None);
+        distances[start] = Some(0);
+        let mut q = VecDeque::from([(start, 0)]);
+        while let Some((h, steps)) = q.pop_front() {
+            for neighbor in h.neighbors() {
+                if distances.get(neighbor) == Some(None) && traversible(self, neighbor) {
+                    distances[neighbor] = Some(steps + 1);
+                    q.push_back((neighbor, steps + 1));
+                }
+            }
+        }
+        Ok(distances)
+    }
+
+    /// Dijkstra's algorithm over adjacent hexagons; see [`DenseGrid::dijkstra`]
+    #[allow(clippy::type_complexity)]
+    pub fn dijkstra<MV, TF, F>(
+        &self,
+        start: Hex,
+        traversible: TF,
+        cost: F,
+    ) -> anyhow::Result<(HexGrid<DijkstraMetric<MV>>, HexGrid<Option<Hex>>)>
+    where
+        MV: std::fmt::Debug
+            + Clone
+            + Copy
+            + PartialEq
+            + Eq
+            + PartialOrd
+            + Ord
+            + num_traits::Zero
+            + std::ops::Add,
+        F: Fn(&Self, Hex, Hex) -> MV,
+        TF: Fn(&Self, Hex) -> bool,
+    {
+        if !self.contains(start) {
+            anyhow::bail!("start point not contained in map");
+        }
+        let mut distances = HexGrid::new_with_dimensions_from(self, DijkstraMetric::Infinite);
+        distances[start] = DijkstraMetric::Finite(MV::zero());
+        let mut preds = HexGrid::new_with_dimensions_from(self, None);
+        let mut visited = HexGrid::new_with_dimensions_from(self, false);
+        let mut unvisited = BinaryHeap::new();
+        unvisited.push((Reverse(MV::zero()), start));
+        while let Some((Reverse(current), h)) = unvisited.pop() {
+            if visited[h] {
+                continue;
+            }
+            visited[h] = true;
+            for neighbor in h.neighbors() {
+                if !self.contains(neighbor) || visited[neighbor] || !traversible(self, neighbor) {
+                    continue;
+                }
+                let next = current + cost(self, h, neighbor);
+                if DijkstraMetric::Finite(next) < distances[neighbor] {
+                    distances[neighbor] = DijkstraMetric::Finite(next);
+                    preds[neighbor] = Some(h);
+                    unvisited.push((Reverse(next), neighbor));
+                }
+            }
+        }
+        Ok((distances, preds))
+    }
+
+    /// Render the grid with hexagons of the given size (center to corner, in
+    /// pixels); pixels outside the grid are white
+    pub fn save_to_image<F: Fn(&V) -> image::Rgb<u8>, P: AsRef<std::path::Path>>(
+        &self,
+        f: F,
+        size: f64,
+        path: P,
+    ) -> anyhow::Result<()> {
+        let centers = self
+            .iter()
+            .map(|(h, _)| h.to_pixel(size))
+            .collect::<Vec<_>>();
+        if centers.is_empty() {
+            anyhow::bail!("can't render a grid with no hexagons");
+        }
+        let min_x = centers.iter().map(|c| c.0).fold(f64::INFINITY, f64::min) - size;
+        let max_x = centers
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max)
+            + size;
+        let min_y = centers.iter().map(|c| c.1).fold(f64::INFINITY, f64::min) - size;
+        let max_y = centers
+            .iter()
+            .map(|c| c.1)
+            .fold(f64::NEG_INFINITY, f64::max)
+            + size;
+        let mut image = image::ImageBuffer::from_pixel(
+            (max_x - min_x).ceil().max(1.0) as u32,
+            (max_y - min_y).ceil().max(1.0) as u32,
+            image::Rgb([255, 255, 255]),
+        );
+        for (px, py, pixel) in image.enumerate_pixels_mut() {
+            let h = Hex::from_pixel(px as f64 + 0.5 + min_x, py as f64 + 0.5 + min_y, size);
+            if let Some(value) = self.get(h) {
+                *pixel = f(&value);
+            }
+        }
+        image.save(path.as_ref())?;
+        Ok(())
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::Index<Hex> for HexGrid<V> {
+    type Output = V;
+
+    fn index(&self, h: Hex) -> &Self::Output {
+        assert!(self.contains(h), "{} is not in the grid", h);
+        &self.cells[Self::point(h)]
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::IndexMut<Hex> for HexGrid<V> {
+    fn index_mut(&mut self, h: Hex) -> &mut Self::Output {
+        assert!(self.contains(h), "{} is not in the grid", h);
+        &mut self.cells[Self::point(h)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hex, HexGrid};
+    use crate::dijkstra_metric::DijkstraMetric;
+
+    #[test]
+    fn test_coordinates() {
+        let h = Hex::new(2, -3);
+        assert_eq!(h.cube(), (2, -3, 1));
+        assert_eq!(Hex::from_cube(2, -3, 1), Some(h));
+        assert_eq!(Hex::from_cube(2, -3, 0), None);
+        assert!(h.neighbors().iter().all(|n| n.distance(h) == 1));
+        assert_eq!(Hex::new(0, 0).distance(Hex::new(3, -1)), 3);
+        assert_eq!(Hex::new(-2, 0).distance(Hex::new(1, 2)), 5);
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let c = Hex::new(1, 1);
+        assert_eq!(c.ring(0), vec![c]);
+        for radius in 1..4 {
+            let ring = c.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.distance(c) == radius));
+            // each hexagon is adjacent to the next one around the ring
+            assert!(ring.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        }
+        let spiral = c.spiral(2);
+        assert_eq!(spiral.len(), 19);
+        assert_eq!(
+            spiral
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            19
+        );
+    }
+
+    #[test]
+    fn test_rotation() {
+        let h = Hex::new(2, -1);
+        assert_eq!(h.rotate_cw().rotate_ccw(), h);
+        let mut r = h;
+        for _ in 0..6 {
+            r = r.rotate_cw();
+            assert_eq!(r.distance(Hex::new(0, 0)), 2);
+        }
+        assert_eq!(r, h);
+        assert_eq!(Hex::DIRECTIONS[0].rotate_ccw(), Hex::DIRECTIONS[1]);
+        let center = Hex::new(5, 5);
+        assert_eq!((center + h).rotate_about(center, 1), center + h.rotate_cw());
+        assert_eq!(
+            (center + h).rotate_about(center, -1),
+            center + h.rotate_ccw()
+        );
+    }
+
+    #[test]
+    fn test_pixels() {
+        for h in Hex::new(0, 0).spiral(3) {
+            let (x, y) = h.to_pixel(10.0);
+            assert_eq!(Hex::from_pixel(x, y, 10.0), h);
+            assert_eq!(Hex::from_pixel(x + 4.0, y - 4.0, 10.0), h);
+        }
+        assert_eq!(Hex::new(0, 1).to_pixel(2.0).1, 3.0);
+    }
+
+    #[test]
+    fn test_hexagon_grid() {
+        let mut g = HexGrid::new_hexagon(Hex::new(0, 0), 2, '.').unwrap();
+        assert_eq!(g.size(), 19);
+        assert_eq!(g.iter().count(), 19);
+        assert!(g.contains(Hex::new(2, -2)));
+        assert!(!g.contains(Hex::new(2, 2)));
+        assert_eq!(g.get(Hex::new(2, 2)), None);
+        assert_eq!(g.set(Hex::new(1, 0), '#'), Some(()));
+        assert_eq!(g[Hex::new(1, 0)], '#');
+        assert!(HexGrid::new_hexagon(Hex::new(0, 0), -1, '.').is_none());
+        let single = HexGrid::new_hexagon(Hex::new(3, 3), 0, '.').unwrap();
+        assert_eq!(single.size(), 1);
+        assert_eq!(single.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_flood_fill_and_paths() {
+        let mut g = HexGrid::<u8>::new_hexagon(Hex::new(0, 0), 2, 0).unwrap();
+        // a wall around the center, missing one side
+        for h in Hex::new(0, 0).ring(1).into_iter().skip(1) {
+            g[h] = 1;
+        }
+        let open = |g: &HexGrid<u8>, h| g[h] == 0;
+        let steps = g.bfs(Hex::new(0, 0), open).unwrap();
+        assert_eq!(steps[Hex::new(0, 0)], Some(0));
+        let gap = Hex::new(0, 0).ring(1)[0];
+        assert_eq!(steps[gap], Some(1));
+        // going around to the opposite side of the wall
+        assert_eq!(steps[Hex::new(0, 0) - gap * 2], Some(7));
+        assert_eq!(steps[Hex::new(0, 0) - gap], None);
+
+        let (costs, preds) = g.dijkstra(Hex::new(0, 0), open, |_, _, _| 1u32).unwrap();
+        assert_eq!(costs[Hex::new(0, 0) - gap * 2], DijkstraMetric::Finite(7));
+        assert_eq!(preds[gap], Some(Hex::new(0, 0)));
+
+        g.flood_fill(Hex::new(0, 0), 2).unwrap();
+        assert_eq!(g.iter().filter(|(_, v)| **v == 2).count(), 14);
+        assert!(g.flood_fill(Hex::new(3, 0), 2).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use std::ops::RangeInclusive;
+
+use num_traits::PrimInt;
+
+/// A static interval tree over inclusive ranges, each carrying a payload.
+///
+/// Intervals are stored sorted by start as an implicit balanced binary tree,
+/// with each subtree annotated with the largest end it contains. Building is
+/// O(n log n); stabbing and overlap queries are O((k + 1) log n) for k
+/// results, since each result can cost a walk down the tree.
+/// Empty ranges (where start > end) are discarded.
+#[derive(Debug, Clone)]
+pub struct IntervalTree<T: PrimInt, P> {
+    intervals: Vec<(RangeInclusive<T>, P)>,
+    /// the largest end in the subtree rooted at each index
+    max_end: Vec<T>,
+    starts: Vec<T>,
+    ends: Vec<T>,
+}
+
+impl<T: PrimInt, P> IntervalTree<T, P> {
+    pub fn new<It: IntoIterator<Item = (RangeInclusive<T>, P)>>(intervals: It) -> Self {
+        let mut intervals = intervals
+            .into_iter()
+            .filter(|(r, _)| !r.is_empty())
+            .collect::<Vec<_>>();
+        intervals.sort_by_key(|(r, _)| (*r.start(), *r.end()));
+        let mut starts = intervals
+            .iter()
+            .map(|(r, _)| *r.start())
+            .collect::<Vec<_>>();
+        let mut ends = intervals.iter().map(|(r, _)| *r.end()).collect::<Vec<_>>();
+        starts.sort_unstable();
+        ends.sort_unstable();
+        let mut tree = Self {
+            max_end: vec![T::zero(); intervals.len()],
+            intervals,
+            starts,
+            ends,
+        };
+        tree.build(0, tree.intervals.len());
+        tree
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = (lo + hi) / 2;
+        let mut max_end = *self.intervals[mid].0.end();
+        for child in [self.build(lo, mid), self.build(mid + 1, hi)]
+            .into_iter()
+            .flatten()
+        {
+            max_end = max_end.max(child);
+        }
+        self.max_end[mid] = max_end;
+        Some(max_end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// All intervals, sorted by start
+    pub fn iter(&self) -> impl Iterator<Item = (&RangeInclusive<T>, &P)> {
+        self.intervals.iter().map(|(r, p)| (r, p))
+    }
+
+    /// Every interval containing the value, sorted by start
+    pub fn stab(&self, value: T) -> Vec<(&RangeInclusive<T>, &P)> {
+        self.overlapping(value..=value)
+    }
+
+    /// Every interval sharing at least one value with the query range, sorted by start
+    pub fn overlapping(&self, range: RangeInclusive<T>) -> Vec<(&RangeInclusive<T>, &P)> {
+        let mut out = vec![];
+        if !range.is_empty() {
+            self.query(0, self.intervals.len(), &range, &mut out);
+        }
+        out
+    }
+
+    fn query<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        range: &RangeInclusive<T>,
+        out: &mut Vec<(&'a RangeInclusive<T>, &'a P)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < *range.start() {
+            // nothing in this subtree reaches the query
+            return;
+        }
+        self.query(lo, mid, range, out);
+        let (r, p) = &self.intervals[mid];
+        if r.start() <= range.end() {
+            if r.end() >= range.start() {
+                out.push((r, p));
+            }
+            self.query(mid + 1, hi, range, out);
+        }
+    }
+
+    /// The number of intervals containing the value. O(log n).
+    pub fn count_covering(&self, value: T) -> usize {
+        let started = self.starts.partition_point(|s| *s <= value);
+        let ended = self.ends.partition_point(|e| *e < value);
+        started - ended
+    }
+
+    /// Split the covered values into maximal runs with the same number of
+    /// covering intervals, in increasing order. Uncovered values are skipped.
+    pub fn coverage(&self) -> Vec<(RangeInclusive<T>, usize)> {
+        let mut out: Vec<(RangeInclusive<T>, usize)> = vec![];
+        let mut starts = self.starts.iter().peekable();
+        // intervals stop covering the value after their end
+        let mut ends = self.ends.iter().peekable();
+        let mut depth = 0usize;
+        let mut current: Option<T> = None;
+        loop {
+            let next_start = starts.peek().copied().copied();
+            let next_end = ends.peek().copied().copied();
+            // the next value at which the depth changes
+            let next = match (next_start, next_end) {
+                (None, None) => break,
+                (Some(s), Some(e)) if s <= e => s,
+                (Some(s), None) => s,
+                (_, Some(e)) => match e.checked_add(&T::one()) {
+                    Some(after) => after,
+                    None => {
+                        // the rest extends to T::max_value()
+                        if let Some(c) = current {
+                            out.push((c..=e, depth));
+                        }
+                        break;
+                    }
+                },
+            };
+            if let Some(c) = current
+                && c < next
+                && depth > 0
+            {
+                out.push((c..=next - T::one(), depth));
+            }
+            while starts.next_if(|s| **s == next).is_some() {
+                depth += 1;
+            }
+            while ends
+                .next_if(|e| e.checked_add(&T::one()) == Some(next))
+                .is_some()
+            {
+                depth -= 1;
+            }
+            current = Some(next);
+        }
+        out
+    }
+}
+
+impl<T: PrimInt, P> FromIterator<(RangeInclusive<T>, P)> for IntervalTree<T, P> {
+    fn from_iter<It: IntoIterator<Item = (RangeInclusive<T>, P)>>(iter: It) -> Self {
+        Self::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalTree;
+
+    fn sample() -> IntervalTree<u64, usize> {
+        [3..=5, 10..=14, 16..=20, 12..=18]
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| (r, i))
+            .collect()
+    }
+
+    #[test]
+    fn test_stab() {
+        let t = sample();
+        assert_eq!(t.len(), 4);
+        let ids = |v| t.stab(v).into_iter().map(|(_, p)| *p).collect::<Vec<_>>();
+        assert!(ids(1).is_empty());
+        assert_eq!(ids(5), vec![0]);
+        assert!(ids(8).is_empty());
+        assert_eq!(ids(12), vec![1, 3]);
+        assert_eq!(ids(17), vec![3, 2]);
+        assert!(ids(32).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let t = sample();
+        let ids = |r| {
+            t.overlapping(r)
+                .into_iter()
+                .map(|(_, p)| *p)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(5..=10), vec![0, 1]);
+        assert!(ids(6..=9).is_empty());
+        assert_eq!(ids(0..=100), vec![0, 1, 3, 2]);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let ranges = (0..200u32)
+            .map(|i| {
+                let start = (i * 7919) % 500;
+                start..=start + (i * 104729) % 40
+            })
+            .collect::<Vec<_>>();
+        let t = ranges
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, r)| (r, i))
+            .collect::<IntervalTree<_, _>>();
+        for v in 0..560 {
+            let mut expected = ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.contains(&v))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            let mut found = t.stab(v).into_iter().map(|(_, p)| *p).collect::<Vec<_>>();
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected);
+            assert_eq!(t.count_covering(v), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_coverage() {
+        let t = sample();
+        assert_eq!(
+            t.coverage(),
+            vec![
+                (3..=5, 1),
+                (10..=11, 1),
+                (12..=14, 2),
+                (15..=15, 1),
+                (16..=18, 2),
+                (19..=20, 1)
+            ]
+        );
+        let t = [(250u8..=255, ()), (253..=255, ())]
+            .into_iter()
+            .collect::<IntervalTree<_, _>>();
+        assert_eq!(t.coverage(), vec![(250..=252, 1), (253..=255, 2)]);
+    }
+}
@@ -0,0 +1,375 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Range;
+
+use num_traits::Zero;
+
+use crate::dimval::DimVal;
+use crate::point::Point;
+use crate::vec3::Vec3;
+
+/// A point with a fixed number of integer-like coordinates, which can be
+/// stored in a [`KdTree`]
+pub trait KdPoint: Copy {
+    type Coord: DimVal + Ord;
+    const DIMENSIONS: usize;
+
+    fn coordinate(&self, axis: usize) -> Self::Coord;
+
+    fn squared_distance_to(&self, other: &Self) -> Self::Coord {
+        (0..Self::DIMENSIONS)
+            .map(|axis| {
+                let d = self.coordinate(axis) - other.coordinate(axis);
+                d * d
+            })
+            .fold(Self::Coord::zero(), |a, b| a + b)
+    }
+}
+
+impl<I: DimVal + Ord> KdPoint for Point<I> {
+    type Coord = I;
+    const DIMENSIONS: usize = 2;
+
+    fn coordinate(&self, axis: usize) -> I {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => panic!("invalid axis {axis}"),
+        }
+    }
+}
+
+impl<I: DimVal + Ord> KdPoint for Vec3<I> {
+    type Coord = I;
+    const DIMENSIONS: usize = 3;
+
+    fn coordinate(&self, axis: usize) -> I {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!("invalid axis {axis}"),
+        }
+    }
+}
+
+/// A point found by a [`KdTree`] query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbor<P: KdPoint> {
+    /// The position of the point in the input the tree was built from
+    pub index: usize,
+    pub point: P,
+    pub squared_distance: P::Coord,
+}
+
+/// A static k-d tree for nearest-neighbor queries under Euclidean distance.
+///
+/// Distances are compared squared, so integer coordinates give exact results.
+/// Ties are broken by input position, so every query is deterministic.
+#[derive(Debug, Clone)]
+pub struct KdTree<P: KdPoint> {
+    points: Vec<P>,
+    /// (point, input index), arranged so the median of each subrange on that
+    /// depth's axis sits at the middle of the subrange
+    nodes: Vec<(P, usize)>,
+}
+
+impl<P: KdPoint> KdTree<P> {
+    pub fn new(points: Vec<P>) -> Self {
+        let mut nodes = points.iter().copied().zip(0..).collect::<Vec<_>>();
+        Self::build(&mut nodes, 0);
+        Self { points, nodes }
+    }
+
+    fn build(nodes: &mut [(P, usize)], depth: usize) {
+        if nodes.len() <= 1 {
+            return;
+        }
+        let mid = nodes.len() / 2;
+        let axis = depth % P::DIMENSIONS;
+        nodes.select_nth_unstable_by_key(mid, |(p, i)| (p.coordinate(axis), *i));
+        let (left, right) = nodes.split_at_mut(mid);
+        Self::build(left, depth + 1);
+        Self::build(&mut right[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The points, in input order
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+
+    /// Split a subrange into (its median, the side nearer to `target`, the far
+    /// side, the signed distance from the splitting plane to `target`)
+    fn split(
+        &self,
+        range: Range<usize>,
+        depth: usize,
+        target: &P,
+    ) -> (usize, Range<usize>, Range<usize>, P::Coord) {
+        let mid = range.start + (range.end - range.start) / 2;
+        let axis = depth % P::DIMENSIONS;
+        let diff = target.coordinate(axis) - self.nodes[mid].0.coordinate(axis);
+        let left = range.start..mid;
+        let right = mid + 1..range.end;
+        if diff < P::Coord::zero() {
+            (mid, left, right, diff)
+        } else {
+            (mid, right, left, diff)
+        }
+    }
+
+    fn neighbor(&self, node: usize, target: &P) -> Neighbor<P> {
+        let (point, index) = self.nodes[node];
+        Neighbor {
+            index,
+            point,
+            squared_distance: point.squared_distance_to(target),
+        }
+    }
+
+    /// The point closest to `target`, if there are any
+    pub fn nearest(&self, target: &P) -> Option<Neighbor<P>> {
+        self.k_nearest(target, 1).pop()
+    }
+
+    /// The `k` points closest to `target` (or every point, if there are fewer),
+    /// closest first
+    pub fn k_nearest(&self, target: &P, k: usize) -> Vec<Neighbor<P>> {
+        let mut best = BinaryHeap::new();
+        if k > 0 {
+            self.search_k(0..self.nodes.len(), 0, target, k, &mut best);
+        }
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|(squared_distance, index)| Neighbor {
+                index,
+                point: self.points[index],
+                squared_distance,
+            })
+            .collect()
+    }
+
+    fn search_k(
+        &self,
+        range: Range<usize>,
+        depth: usize,
+        target: &P,
+        k: usize,
+        best: &mut BinaryHeap<(P::Coord, usize)>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        let (mid, near, far, diff) = self.split(range, depth, target);
+        let n = self.neighbor(mid, target);
+        let candidate = (n.squared_distance, n.index);
+        if best.len() < k {
+            best.push(candidate);
+        } else if best.peek().is_some_and(|worst| candidate < *worst) {
+            best.pop();
+            best.push(candidate);
+        }
+        self.search_k(near, depth + 1, target, k, best);
+        if best.len() < k || best.peek().is_some_and(|(worst, _)| diff * diff <= *worst) {
+            self.search_k(far, depth + 1, target, k, best);
+        }
+    }
+
+    /// Every point within `radius` of `target` (inclusive), closest first
+    pub fn within_radius(&self, target: &P, radius: P::Coord) -> Vec<Neighbor<P>> {
+        let mut found = vec![];
+        self.search_radius(0..self.nodes.len(), 0, target, radius * radius, &mut found);
+        found.sort_by_key(|n| (n.squared_distance, n.index));
+        found
+    }
+
+    fn search_radius(
+        &self,
+        range: Range<usize>,
+        depth: usize,
+        target: &P,
+        squared_radius: P::Coord,
+        found: &mut Vec<Neighbor<P>>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        let (mid, near, far, diff) = self.split(range, depth, target);
+        let n = self.neighbor(mid, target);
+        if n.squared_distance <= squared_radius {
+            found.push(n);
+        }
+        self.search_radius(near, depth + 1, target, squared_radius, found);
+        if diff * diff <= squared_radius {
+            self.search_radius(far, depth + 1, target, squared_radius, found);
+        }
+    }
+
+    /// Every pair of distinct points as `(i, j, squared_distance)` with
+    /// `i < j` (indices into the input), in order of increasing distance, then
+    /// `i`, then `j`. Pairs are found lazily, so taking the first few of n
+    /// points doesn't cost O(n²).
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, P> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            streams: vec![NeighborStream::default(); self.len()],
+            heap: BinaryHeap::new(),
+        };
+        for i in 0..self.len() {
+            pairs.refill(i);
+        }
+        pairs
+    }
+}
+
+impl<P: KdPoint> FromIterator<P> for KdTree<P> {
+    fn from_iter<It: IntoIterator<Item = P>>(iter: It) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// The neighbors of one point with larger indices, fetched in growing batches
+#[derive(Debug, Clone)]
+struct NeighborStream<C> {
+    /// how many nearest neighbors (of any index) have been fetched so far
+    fetched: usize,
+    pending: VecDeque<(C, usize)>,
+}
+
+impl<C> Default for NeighborStream<C> {
+    fn default() -> Self {
+        Self {
+            fetched: 0,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+pub struct ClosestPairs<'a, P: KdPoint> {
+    tree: &'a KdTree<P>,
+    streams: Vec<NeighborStream<P::Coord>>,
+    heap: BinaryHeap<Reverse<(P::Coord, usize, usize)>>,
+}
+
+impl<P: KdPoint> ClosestPairs<'_, P> {
+    /// Queue up the next unreported partner of point `i`, if any
+    fn refill(&mut self, i: usize) {
+        let stream = &mut self.streams[i];
+        while stream.pending.is_empty() && stream.fetched < self.tree.len() {
+            let k = (stream.fetched * 2).max(8).min(self.tree.len());
+            let found = self.tree.k_nearest(&self.tree.points[i], k);
+            stream.pending.extend(
+                found[stream.fetched..]
+                    .iter()
+                    .filter(|n| n.index > i)
+                    .map(|n| (n.squared_distance, n.index)),
+            );
+            stream.fetched = k;
+        }
+        if let Some((d, j)) = stream.pending.pop_front() {
+            self.heap.push(Reverse((d, i, j)));
+        }
+    }
+}
+
+impl<P: KdPoint> Iterator for ClosestPairs<'_, P> {
+    type Item = (usize, usize, P::Coord);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((d, i, j)) = self.heap.pop()?;
+        self.refill(i);
+        Some((i, j, d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KdPoint, KdTree};
+    use crate::point::Point;
+    use crate::vec3::Vec3;
+
+    fn sample() -> Vec<Vec3> {
+        (0..60i64)
+            .map(|i| Vec3::new((i * 37) % 23, (i * 11) % 17, (i * 53) % 29))
+            .collect()
+    }
+
+    fn brute_sorted(points: &[Vec3], target: &Vec3) -> Vec<(i64, usize)> {
+        let mut all = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.squared_distance_to(target), i))
+            .collect::<Vec<_>>();
+        all.sort();
+        all
+    }
+
+    #[test]
+    fn test_nearest_queries() {
+        let points = sample();
+        let tree = KdTree::new(points.clone());
+        for target in [
+            Vec3::new(0, 0, 0),
+            Vec3::new(11, 8, 14),
+            Vec3::new(30, -4, 7),
+        ] {
+            let expected = brute_sorted(&points, &target);
+            let nearest = tree.nearest(&target).unwrap();
+            assert_eq!((nearest.squared_distance, nearest.index), expected[0]);
+            let k = tree
+                .k_nearest(&target, 7)
+                .into_iter()
+                .map(|n| (n.squared_distance, n.index))
+                .collect::<Vec<_>>();
+            assert_eq!(k, expected[..7]);
+            let within = tree
+                .within_radius(&target, 9)
+                .into_iter()
+                .map(|n| (n.squared_distance, n.index))
+                .collect::<Vec<_>>();
+            let expected_within = expected
+                .into_iter()
+                .filter(|(d, _)| *d <= 81)
+                .collect::<Vec<_>>();
+            assert_eq!(within, expected_within);
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs() {
+        let points = sample();
+        let tree = points.iter().copied().collect::<KdTree<_>>();
+        let mut expected = vec![];
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                expected.push((points[i].squared_distance_to(&points[j]), i, j));
+            }
+        }
+        expected.sort();
+        let found = tree
+            .closest_pairs()
+            .map(|(i, j, d)| (d, i, j))
+            .collect::<Vec<_>>();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_points_2d() {
+        let tree = KdTree::new(vec![Point::new(0, 0), Point::new(5, 5), Point::new(1, 2)]);
+        assert_eq!(tree.nearest(&Point::new(4, 4)).unwrap().index, 1);
+        assert_eq!(tree.closest_pairs().next(), Some((0, 2, 5)));
+        assert!(
+            KdTree::<Point>::new(vec![])
+                .nearest(&Point::new(0, 0))
+                .is_none()
+        );
+    }
+}
@@ -1,13 +1,51 @@
+mod compressed;
 mod dijkstra_metric;
 mod dimval;
+mod direction;
+mod disjoint_set;
 mod grid;
+mod grid3;
+mod grid_n;
+mod grid_view;
+mod hex;
+mod interval_tree;
+mod kd_tree;
+mod mst;
 pub mod petgraph_bellman_ford_multi;
+mod pattern;
 mod point;
+mod point_n;
+mod polygon;
+mod range_set;
+mod raycast;
+mod rect;
+mod sweep;
+mod transform;
 mod vec3;
 
+pub use compressed::{AreaSums, CompressedAxis, CompressedCoords};
 pub use dijkstra_metric::DijkstraMetric;
+pub use direction::Direction;
+pub use disjoint_set::DisjointSet;
 pub use grid::DenseGrid;
 pub use grid::HasEmpty;
+pub use grid::ParseError;
+pub use grid::ParseMode;
+pub use grid3::DenseGrid3;
+pub use grid_n::DenseGridN;
+pub use grid_view::{GridView, GridViewMut};
+pub use hex::{Hex, HexGrid};
+pub use interval_tree::IntervalTree;
+pub use kd_tree::{ClosestPairs, KdPoint, KdTree, Neighbor};
+pub use mst::{Kruskal, prim_complete};
+pub use pattern::{Orientation, PatternMatch};
 pub use point::Point;
 pub use point::Rotation;
+pub use point_n::PointN;
+pub use polygon::RectilinearPolygon;
+pub use range_set::RangeSet;
+pub use raycast::Ray;
+pub use rect::Rect;
+pub use sweep::{Segments, SweepLine};
+pub use transform::AffineTransform;
 pub use vec3::Vec3;
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeIdentifiers};
+
+use crate::disjoint_set::DisjointSet;
+
+/// Kruskal's algorithm, run incrementally: edges are considered in order of
+/// increasing weight (ties keep their input order), and iterating yields the
+/// edges which join two previously separate components, i.e. the minimum
+/// spanning forest. The components can be inspected between steps.
+#[derive(Debug, Clone)]
+pub struct Kruskal<K, W, E = std::vec::IntoIter<(K, K, W)>> {
+    edges: E,
+    components: DisjointSet<K>,
+    considered: usize,
+    weight: PhantomData<W>,
+}
+
+impl<K: Hash + Eq + Clone, W: Ord> Kruskal<K, W> {
+    /// Nodes which appear in edges are added automatically; `nodes` only needs
+    /// to list the rest, but it's harmless to list everything.
+    pub fn new<N, E>(nodes: N, edges: E) -> Self
+    where
+        N: IntoIterator<Item = K>,
+        E: IntoIterator<Item = (K, K, W)>,
+    {
+        let mut components = nodes.into_iter().collect::<DisjointSet<_>>();
+        let mut edges = edges.into_iter().collect::<Vec<_>>();
+        for (a, b, _) in edges.iter() {
+            components.insert(a.clone());
+            components.insert(b.clone());
+        }
+        edges.sort_by(|(_, _, lhs), (_, _, rhs)| lhs.cmp(rhs));
+        Self {
+            edges: edges.into_iter(),
+            components,
+            considered: 0,
+            weight: PhantomData,
+        }
+    }
+}
+
+impl<K, W, E> Kruskal<K, W, E>
+where
+    K: Hash + Eq + Clone,
+    E: Iterator<Item = (K, K, W)>,
+{
+    /// Run over edges which are already in order of increasing weight, pulling
+    /// them from the iterator only as they're considered. Unlike
+    /// [`Kruskal::new`], `nodes` must list every node.
+    pub fn from_sorted<N: IntoIterator<Item = K>>(nodes: N, edges: E) -> Self {
+        Self {
+            edges,
+            components: nodes.into_iter().collect(),
+            considered: 0,
+            weight: PhantomData,
+        }
+    }
+
+    /// Consider the next cheapest edge, returning it and whether it joined
+    /// two components
+    pub fn step(&mut self) -> Option<((K, K, W), bool)> {
+        let edge = self.edges.next()?;
+        self.considered += 1;
+        let accepted = self.components.union(&edge.0, &edge.1);
+        Some((edge, accepted))
+    }
+
+    /// Consider up to `count` more edges, whether or not they're accepted
+    pub fn consider(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.step().is_none() {
+                break;
+            }
+        }
+    }
+
+    /// The number of edges considered so far
+    pub fn considered(&self) -> usize {
+        self.considered
+    }
+
+    pub fn components(&self) -> &DisjointSet<K> {
+        &self.components
+    }
+
+    /// How many components there currently are of each size
+    pub fn component_size_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for size in self.components.component_sizes() {
+            *histogram.entry(size).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Keep accepting edges until every node is connected, returning the edge
+    /// which completed connectivity. Returns None if the graph was already
+    /// connected or can't be connected by the remaining edges.
+    pub fn connecting_edge(&mut self) -> Option<(K, K, W)> {
+        if self.components.num_components() <= 1 {
+            return None;
+        }
+        while let Some((edge, accepted)) = self.step() {
+            if accepted && self.components.num_components() == 1 {
+                return Some(edge);
+            }
+        }
+        None
+    }
+}
+
+impl<N, W> Kruskal<N, W>
+where
+    N: Hash + Eq + Clone,
+    W: Ord + Clone,
+{
+    /// Run over the nodes and edges of a petgraph graph (which is treated as
+    /// undirected)
+    pub fn from_graph<G>(graph: G) -> Self
+    where
+        G: IntoEdgeReferences<NodeId = N, EdgeWeight = W> + IntoNodeIdentifiers,
+    {
+        Self::new(
+            graph.node_identifiers(),
+            graph
+                .edge_references()
+                .map(|e| (e.source(), e.target(), e.weight().clone())),
+        )
+    }
+}
+
+impl<K, W, E> Iterator for Kruskal<K, W, E>
+where
+    K: Hash + Eq + Clone,
+    E: Iterator<Item = (K, K, W)>,
+{
+    type Item = (K, K, W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (edge, accepted) = self.step()?;
+            if accepted {
+                return Some(edge);
+            }
+        }
+    }
+}
+
+/// Prim's algorithm over the complete graph of `count` nodes, where `weight`
+/// gives the weight of the edge between two node indices. This is O(n²),
+/// which beats sorting all n² edges when every pair is a candidate. Returns the
+/// edges of the minimum spanning tree in the order they were added, each as
+/// (node already in the tree, new node, weight).
+pub fn prim_complete<W, F>(count: usize, weight: F) -> Vec<(usize, usize, W)>
+where
+    W: Ord + Copy,
+    F: Fn(usize, usize) -> W,
+{
+    let mut out = Vec::with_capacity(count.saturating_sub(1));
+    if count == 0 {
+        return out;
+    }
+    let mut in_tree = vec![false; count];
+    // the cheapest known edge from the tree to each node outside it
+    let mut best: Vec<Option<(usize, W)>> = vec![None; count];
+    let mut newest = 0;
+    in_tree[0] = true;
+    for _ in 1..count {
+        let mut next: Option<(usize, usize, W)> = None;
+        for node in 0..count {
+            if in_tree[node] {
+                continue;
+            }
+            let w = weight(newest, node);
+            if best[node].is_none_or(|(_, b)| w < b) {
+                best[node] = Some((newest, w));
+            }
+            let (from, w) = best[node].expect("just set");
+            if next.is_none_or(|(_, _, n)| w < n) {
+                next = Some((from, node, w));
+            }
+        }
+        let (from, node, w) = next.expect("a node outside the tree");
+        in_tree[node] = true;
+        newest = node;
+        out.push((from, node, w));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kruskal, prim_complete};
+    use petgraph::graph::UnGraph;
+
+    fn edges() -> Vec<(char, char, u32)> {
+        vec![
+            ('a', 'b', 4),
+            ('a', 'c', 1),
+            ('b', 'c', 2),
+            ('c', 'd', 5),
+            ('b', 'd', 8),
+            ('e', 'f', 3),
+        ]
+    }
+
+    #[test]
+    fn test_spanning_forest() {
+        let forest = Kruskal::new(['g'], edges()).collect::<Vec<_>>();
+        assert_eq!(
+            forest,
+            vec![('a', 'c', 1), ('b', 'c', 2), ('e', 'f', 3), ('c', 'd', 5)]
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut k = Kruskal::new([], edges());
+        assert_eq!(k.components().num_components(), 6);
+        k.consider(4);
+        assert_eq!(k.considered(), 4);
+        // a-b was rejected, so a,b,c are together and d is alone
+        assert_eq!(
+            k.component_size_histogram(),
+            [(1, 1), (2, 1), (3, 1)].into()
+        );
+        assert_eq!(k.connecting_edge(), None);
+        let mut k = Kruskal::new([], edges().into_iter().chain([('d', 'e', 9)]));
+        assert_eq!(k.connecting_edge(), Some(('d', 'e', 9)));
+        assert_eq!(k.considered(), 7);
+    }
+
+    #[test]
+    fn test_from_graph() {
+        let mut g = UnGraph::<(), u32>::new_undirected();
+        let nodes = (0..4).map(|_| g.add_node(())).collect::<Vec<_>>();
+        g.add_edge(nodes[0], nodes[1], 3);
+        g.add_edge(nodes[1], nodes[2], 1);
+        g.add_edge(nodes[0], nodes[2], 2);
+        let k = Kruskal::from_graph(&g);
+        assert_eq!(k.components().num_components(), 4);
+        let total: u32 = k.map(|(_, _, w)| w).sum();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn test_prim_matches_kruskal() {
+        let points = [(0i64, 0i64), (5, 1), (2, 7), (9, 9), (3, 3), (8, 2)];
+        let distance = |a: usize, b: usize| {
+            (points[a].0 - points[b].0).abs() + (points[a].1 - points[b].1).abs()
+        };
+        let prim_total: i64 = prim_complete(points.len(), distance)
+            .into_iter()
+            .map(|(_, _, w)| w)
+            .sum();
+        let all_edges = (0..points.len())
+            .flat_map(|a| (a + 1..points.len()).map(move |b| (a, b)))
+            .map(|(a, b)| (a, b, distance(a, b)));
+        let kruskal_total: i64 = Kruskal::new([], all_edges).map(|(_, _, w)| w).sum();
+        assert_eq!(prim_total, kruskal_total);
+        assert_eq!(prim_complete(points.len(), distance).len(), 5);
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use super::grid::DenseGrid;
+use super::point::{Point, Rotation};
+use super::transform::AffineTransform;
+
+type Index = i64;
+
+/// One of the 8 rotations/reflections of a rectangular grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Orientation {
+    Identity,
+    /// Rotated 90° clockwise
+    Rotate90,
+    Rotate180,
+    /// Rotated 90° counter-clockwise
+    Rotate270,
+    /// Mirrored left-to-right
+    FlipHorizontal,
+    /// Mirrored top-to-bottom
+    FlipVertical,
+    /// Reflected across the main (upper-left to lower-right) diagonal
+    Transpose,
+    /// Reflected across the anti (upper-right to lower-left) diagonal
+    AntiTranspose,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = [
+        Orientation::Identity,
+        Orientation::Rotate90,
+        Orientation::Rotate180,
+        Orientation::Rotate270,
+        Orientation::FlipHorizontal,
+        Orientation::FlipVertical,
+        Orientation::Transpose,
+        Orientation::AntiTranspose,
+    ];
+
+    /// The rotation or reflection about the origin. Orientations are
+    /// expressed in terms of [`AffineTransform`] so the two can't disagree.
+    pub fn transform(&self) -> AffineTransform {
+        match self {
+            Orientation::Identity => AffineTransform::identity(),
+            Orientation::Rotate90 => AffineTransform::rotation(Rotation::CW),
+            Orientation::Rotate180 => AffineTransform::rotation(Rotation::Mirror),
+            Orientation::Rotate270 => AffineTransform::rotation(Rotation::CCW),
+            Orientation::FlipHorizontal => AffineTransform::reflect_x(),
+            Orientation::FlipVertical => AffineTransform::reflect_y(),
+            Orientation::Transpose => AffineTransform::transpose(),
+            Orientation::AntiTranspose => AffineTransform::anti_transpose(),
+        }
+    }
+
+    /// The transform taking a `width` x `height` rectangle whose upper-left
+    /// corner is `(0, 0)` onto its reoriented copy, also with its upper-left
+    /// corner at `(0, 0)`
+    pub fn transform_within(&self, width: usize, height: usize) -> AffineTransform {
+        let t = self.transform();
+        let far = Point::new(width as Index - 1, height as Index - 1);
+        let corner = t.apply(Point::new(0, 0)).component_min(t.apply(far));
+        t.then(&AffineTransform::translation(-corner))
+    }
+
+    /// Map a coordinate within a `width` x `height` rectangle whose upper-left
+    /// corner is `(0, 0)` to its position after applying this orientation
+    pub fn transform_point(&self, p: Point<Index>, width: usize, height: usize) -> Point<Index> {
+        self.transform_within(width, height).apply(p)
+    }
+
+    /// Produce a reoriented copy of the grid, with its upper-left corner at `(0, 0)`
+    pub fn apply<V: Clone + fmt::Debug>(&self, g: &DenseGrid<V>) -> DenseGrid<V> {
+        let t = AffineTransform::translation(-g.origin())
+            .then(&self.transform_within(g.width(), g.height()));
+        g.transformed(&t)
+            .expect("rotations and reflections map the grid onto itself")
+    }
+}
+
+impl From<Orientation> for AffineTransform {
+    fn from(o: Orientation) -> Self {
+        o.transform()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PatternMatch {
+    /// Where the upper-left corner of the (reoriented) pattern lies in the grid
+    pub origin: Point<Index>,
+    pub orientation: Orientation,
+}
+
+impl<V: Clone + fmt::Debug> DenseGrid<V> {
+    /// Find every place where `pattern` occurs in this grid. `matcher` is
+    /// called with a pattern cell and a grid cell, so wildcards can be
+    /// expressed by returning true unconditionally for some pattern values.
+    pub fn find_pattern<P, F>(&self, pattern: &DenseGrid<P>, matcher: F) -> Vec<Point<Index>>
+    where
+        P: Clone + fmt::Debug + PartialEq,
+        F: Fn(&P, &V) -> bool,
+    {
+        self.find_pattern_oriented(pattern, &[Orientation::Identity], matcher)
+            .into_iter()
+            .map(|m| m.origin)
+            .collect()
+    }
+
+    /// Same as [`DenseGrid::find_pattern`], but try each of the given
+    /// orientations of the pattern (e.g., [`Orientation::ALL`]). Orientations
+    /// which produce the same pattern as an earlier one are skipped, so a
+    /// symmetric pattern is only reported once per location.
+    pub fn find_pattern_oriented<P, F>(
+        &self,
+        pattern: &DenseGrid<P>,
+        orientations: &[Orientation],
+        matcher: F,
+    ) -> Vec<PatternMatch>
+    where
+        P: Clone + fmt::Debug + PartialEq,
+        F: Fn(&P, &V) -> bool,
+    {
+        let mut seen: Vec<DenseGrid<P>> = vec![];
+        let mut matches = vec![];
+        for orientation in orientations {
+            let oriented = orientation.apply(pattern);
+            if seen.contains(&oriented) {
+                continue;
+            }
+            for window in self.windows(oriented.width(), oriented.height()) {
+                if oriented.iter().all(|(p, pv)| matcher(pv, &window[p])) {
+                    matches.push(PatternMatch {
+                        origin: window.origin(),
+                        orientation: *orientation,
+                    });
+                }
+            }
+            seen.push(oriented);
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AffineTransform, DenseGrid, Orientation, PatternMatch, Point};
+
+    #[test]
+    fn test_orientations() {
+        let g = DenseGrid::from_input("ab\ncd\nef", |c| c);
+        let render = |o: Orientation| o.apply(&g).to_string();
+        assert_eq!(render(Orientation::Identity), "ab\ncd\nef\n");
+        assert_eq!(render(Orientation::Rotate90), "eca\nfdb\n");
+        assert_eq!(render(Orientation::Rotate180), "fe\ndc\nba\n");
+        assert_eq!(render(Orientation::Rotate270), "bdf\nace\n");
+        assert_eq!(render(Orientation::FlipHorizontal), "ba\ndc\nfe\n");
+        assert_eq!(render(Orientation::FlipVertical), "ef\ncd\nab\n");
+        assert_eq!(render(Orientation::Transpose), "ace\nbdf\n");
+        assert_eq!(render(Orientation::AntiTranspose), "fdb\neca\n");
+        // the reoriented copy lands at the origin wherever the original was
+        let moved = g.transformed(&AffineTransform::translation(Point::new(5, -3)));
+        assert_eq!(
+            Orientation::Rotate90.apply(&moved.unwrap()),
+            Orientation::Rotate90.apply(&g)
+        );
+    }
+
+    #[test]
+    fn test_transform_within() {
+        let p = Point::new(2, 5);
+        for o in Orientation::ALL {
+            let t = o.transform_within(3, 6);
+            // the rectangle's corners stay within the reoriented rectangle
+            let corners = [Point::new(0, 0), p].map(|c| t.apply(c));
+            let swapped = o.transform().xx == 0;
+            let (w, h) = if swapped { (6, 3) } else { (3, 6) };
+            assert!(
+                corners
+                    .iter()
+                    .all(|c| (0..w).contains(&c.x) && (0..h).contains(&c.y))
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let g = DenseGrid::from_input("#....\n##..#\n...##", |c| c);
+        let pattern = DenseGrid::from_input("#?\n##", |c| c);
+        let matcher = |p: &char, v: &char| *p == '?' || p == v;
+        assert_eq!(g.find_pattern(&pattern, matcher), vec![Point::new(0, 0)]);
+        let matches = g.find_pattern_oriented(&pattern, &Orientation::ALL, matcher);
+        assert_eq!(
+            matches,
+            vec![
+                PatternMatch {
+                    origin: Point::new(0, 0),
+                    orientation: Orientation::Identity
+                },
+                PatternMatch {
+                    origin: Point::new(3, 1),
+                    orientation: Orientation::Rotate270
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symmetric_pattern_reported_once() {
+        let g = DenseGrid::from_input("xx\nxx", |c| c);
+        let pattern = DenseGrid::from_input("x", |c| c);
+        let matches = g.find_pattern_oriented(&pattern, &Orientation::ALL, |p, v| p == v);
+        assert_eq!(matches.len(), 4);
+    }
+}
@@ -7,12 +7,14 @@ use petgraph::visit::{IntoEdges, IntoNodeIdentifiers, NodeCount, NodeIndexable};
 use petgraph::algo::{FloatMeasure, NegativeCycle};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Paths<NodeId, EdgeWeight> {
     pub distances: Vec<EdgeWeight>,
     pub predecessors: Vec<Option<NodeId>>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiPaths<NodeId, EdgeWeight> {
     pub distances: Vec<EdgeWeight>,
     pub predecessors: Vec<Option<Vec<NodeId>>>,
@@ -120,3 +122,35 @@ where
     }
     (distance, predecessor)
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{MultiPaths, Paths, bellman_ford_multi_predecessors};
+    use petgraph::prelude::*;
+
+    #[test]
+    fn test_serde() {
+        let mut g = Graph::<(), f64>::new();
+        let a = g.add_node(());
+        let b = g.add_node(());
+        g.add_node(());
+        g.extend_with_edges([(0, 1, 1.0), (1, 2, 1.0), (0, 2, 2.0)]);
+        let multi = bellman_ford_multi_predecessors(&g, a).unwrap();
+        let s = serde_json::to_string(&multi).unwrap();
+        let decoded: MultiPaths<NodeIndex, f64> = serde_json::from_str(&s).unwrap();
+        assert_eq!(decoded.distances, multi.distances);
+        assert_eq!(
+            decoded.predecessors,
+            vec![None, Some(vec![a]), Some(vec![a, b])]
+        );
+
+        let paths = Paths {
+            distances: vec![0.0, 1.0],
+            predecessors: vec![None, Some(a)],
+        };
+        let s = serde_json::to_string(&paths).unwrap();
+        let decoded: Paths<NodeIndex, f64> = serde_json::from_str(&s).unwrap();
+        assert_eq!(decoded.distances, paths.distances);
+        assert_eq!(decoded.predecessors, paths.predecessors);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::dimval::DimVal;
-use std::cmp::Ordering;
 use std::fmt;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rotation {
     CW,
     CCW,
@@ -9,6 +9,7 @@ pub enum Rotation {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<I: DimVal = i64> {
     pub x: I,
     pub y: I,
@@ -29,14 +30,63 @@ impl<I: DimVal> Point<I> {
         Point::new(self.y, self.x)
     }
 
+    /// Manhattan distance as a `usize`, e.g. for counting steps. Panics if
+    /// it can't be represented; see [`Point::checked_manhattan_distance_to`].
+    ///
+    /// The `_to` distances return a `usize`; the others (like
+    /// [`Point::manhattan_distance`]) return the coordinate type.
     pub fn manhattan_distance_to(&self, other: Point<I>) -> usize {
-        ((self.x - other.x).abs() + (self.y - other.y).abs())
-            .to_u64()
-            .unwrap() as usize
+        self.checked_manhattan_distance_to(other)
+            .expect("distance does not fit in a usize")
     }
 
+    /// Manhattan distance as a `usize`, or None if it can't be represented
+    pub fn checked_manhattan_distance_to(&self, other: Point<I>) -> Option<usize> {
+        self.manhattan_distance(other).to_usize()
+    }
+
+    /// Manhattan distance in the coordinate type
+    pub fn manhattan_distance(&self, other: Point<I>) -> I {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The number of king's moves between the two points (i.e., max of the
+    /// distances along each axis)
+    pub fn chebyshev_distance(&self, other: Point<I>) -> I {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        if dx > dy { dx } else { dy }
+    }
+
+    pub fn squared_euclidean_distance(&self, other: Point<I>) -> I {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        dx * dx + dy * dy
+    }
+
+    /// Component-wise minimum of the two points
+    pub fn component_min(&self, other: Point<I>) -> Self {
+        Point::new(
+            if other.x < self.x { other.x } else { self.x },
+            if other.y < self.y { other.y } else { self.y },
+        )
+    }
+
+    /// Component-wise maximum of the two points
+    pub fn component_max(&self, other: Point<I>) -> Self {
+        Point::new(
+            if other.x > self.x { other.x } else { self.x },
+            if other.y > self.y { other.y } else { self.y },
+        )
+    }
+
+    /// Component-wise sign; useful for turning an offset into a unit step
+    pub fn signum(&self) -> Self {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Rotate about the origin
     pub fn rotate_by(&self, r: Rotation) -> Self {
-        assert!(self.x == I::zero() || self.y == I::zero());
         let neg_one = I::zero() - I::one();
         match r {
             Rotation::Mirror => Point::new(neg_one * self.x, neg_one * self.y),
@@ -84,12 +134,33 @@ impl Point<i64> {
 }
 
 impl<I: DimVal + Ord> Point<I> {
+    /// Rasterize the line from this point to `other` (inclusive of both ends)
+    /// using Bresenham's algorithm. Works for any slope; a zero-length line
+    /// yields just this point.
     pub fn line_to(&self, other: Point<I>) -> impl Iterator<Item = Point<I>> {
         LineToIter::new(*self, other)
     }
+
+    /// Same as [`Point::line_to`], but only for horizontal, vertical, or exactly
+    /// 45° lines; returns None for any other slope.
+    pub fn diagonal_line_to(&self, other: Point<I>) -> Option<impl Iterator<Item = Point<I>>> {
+        let delta = other - *self;
+        if delta.x == I::zero() || delta.y == I::zero() || delta.x.abs() == delta.y.abs() {
+            Some(LineToIter::new(*self, other))
+        } else {
+            None
+        }
+    }
+
+    /// Every cell touched by the segment from this point to `other`, treating
+    /// each point as the center of a unit cell. Where the segment passes
+    /// exactly through a corner, both cells beside the corner are included.
+    pub fn supercover_line_to(&self, other: Point<I>) -> impl Iterator<Item = Point<I>> {
+        SupercoverIter::new(*self, other)
+    }
 }
 
-impl fmt::Display for Point {
+impl<I: DimVal> fmt::Display for Point<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
     }
@@ -128,58 +199,176 @@ impl<I: DimVal> std::ops::Mul<I> for Point<I> {
     }
 }
 
+impl<I: DimVal> std::ops::Div<I> for Point<I> {
+    type Output = Self;
+
+    fn div(self, other: I) -> Self {
+        Point {
+            x: self.x / other,
+            y: self.y / other,
+        }
+    }
+}
+
+impl<I: DimVal> std::ops::Neg for Point<I> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<I: DimVal> std::ops::AddAssign for Point<I> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<I: DimVal> std::ops::SubAssign for Point<I> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<I: DimVal> std::iter::Sum for Point<I> {
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Point::new(I::zero(), I::zero()), |a, b| a + b)
+    }
+}
+
+impl<'a, I: DimVal> std::iter::Sum<&'a Point<I>> for Point<I> {
+    fn sum<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<I: DimVal> From<(I, I)> for Point<I> {
+    fn from((x, y): (I, I)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl<I: DimVal> From<Point<I>> for (I, I) {
+    fn from(p: Point<I>) -> Self {
+        p.as_tuple()
+    }
+}
+
 #[derive(Debug)]
 struct LineToIter<I: DimVal> {
-    start: Point<I>,
+    current: Point<I>,
     end: Point<I>,
-    direction: Point<I>,
+    dx: I,
+    dy: I,
+    step: Point<I>,
+    err: I,
     done: bool,
 }
 
 impl<I: DimVal + Ord> LineToIter<I> {
     fn new(start: Point<I>, end: Point<I>) -> Self {
-        debug_assert!(start.x == end.x || start.y == end.y);
-        let zero = I::zero();
-        let one = I::one();
-        let neg_one = one.neg();
-        let dir = match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
-            (Ordering::Less, _) => Point { x: one, y: zero },
-            (Ordering::Greater, _) => Point {
-                x: neg_one,
-                y: zero,
-            },
-            (Ordering::Equal, Ordering::Less) => Point { x: zero, y: one },
-            (Ordering::Equal, Ordering::Greater) => Point {
-                x: zero,
-                y: neg_one,
-            },
-            (Ordering::Equal, Ordering::Equal) => unreachable!(),
-        };
+        let dx = (end.x - start.x).abs();
+        let dy = -(end.y - start.y).abs();
         Self {
-            start,
+            current: start,
             end,
-            direction: dir,
+            dx,
+            dy,
+            step: Point::new((end.x - start.x).signum(), (end.y - start.y).signum()),
+            err: dx + dy,
             done: false,
         }
     }
 }
 
-impl<I: DimVal> Iterator for LineToIter<I> {
+impl<I: DimVal + Ord> Iterator for LineToIter<I> {
     type Item = Point<I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.done {
             return None;
         }
-        let current = self.start;
-        if self.start == self.end {
-            self.done = true
+        let current = self.current;
+        if current == self.end {
+            self.done = true;
+            return Some(current);
+        }
+        let e2 = self.err + self.err;
+        if e2 >= self.dy {
+            self.err = self.err + self.dy;
+            self.current.x = self.current.x + self.step.x;
+        }
+        if e2 <= self.dx {
+            self.err = self.err + self.dx;
+            self.current.y = self.current.y + self.step.y;
         }
-        self.start = self.start + self.direction;
         Some(current)
     }
 }
 
+#[derive(Debug)]
+struct SupercoverIter<I: DimVal> {
+    current: Point<I>,
+    nx: I,
+    ny: I,
+    ix: I,
+    iy: I,
+    step: Point<I>,
+    pending: Vec<Point<I>>,
+}
+
+impl<I: DimVal + Ord> SupercoverIter<I> {
+    fn new(start: Point<I>, end: Point<I>) -> Self {
+        Self {
+            current: start,
+            nx: (end.x - start.x).abs(),
+            ny: (end.y - start.y).abs(),
+            ix: I::zero(),
+            iy: I::zero(),
+            step: Point::new((end.x - start.x).signum(), (end.y - start.y).signum()),
+            pending: vec![start],
+        }
+    }
+}
+
+impl<I: DimVal + Ord> Iterator for SupercoverIter<I> {
+    type Item = Point<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(p) = self.pending.pop() {
+            return Some(p);
+        }
+        if self.ix >= self.nx && self.iy >= self.ny {
+            return None;
+        }
+        let one = I::one();
+        let two = one + one;
+        // compare where the segment crosses the next vertical vs. horizontal cell edge
+        let decision = (one + two * self.ix) * self.ny - (one + two * self.iy) * self.nx;
+        if decision == I::zero() {
+            let diagonal = self.current + self.step;
+            self.pending.push(diagonal);
+            self.pending
+                .push(Point::new(self.current.x, self.current.y + self.step.y));
+            self.current = diagonal;
+            self.ix = self.ix + one;
+            self.iy = self.iy + one;
+            Some(Point::new(diagonal.x, diagonal.y - self.step.y))
+        } else if decision < I::zero() {
+            self.current.x = self.current.x + self.step.x;
+            self.ix = self.ix + one;
+            Some(self.current)
+        } else {
+            self.current.y = self.current.y + self.step.y;
+            self.iy = self.iy + one;
+            Some(self.current)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Point, Rotation};
@@ -190,6 +379,54 @@ mod tests {
         assert_eq!(Point::new(0, 1).transpose(), Point::new(1, 0));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let p = Point::new(3, -4);
+        let s = serde_json::to_string(&p).unwrap();
+        assert_eq!(s, r#"{"x":3,"y":-4}"#);
+        assert_eq!(serde_json::from_str::<Point>(&s).unwrap(), p);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, -2);
+        let b = Point::new(4, 2);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.manhattan_distance_to(b), 7);
+        assert_eq!(a.checked_manhattan_distance_to(b), Some(7));
+        let far = Point::<i128>::new(i128::from(u64::MAX) * 4, 0);
+        assert_eq!(far.checked_manhattan_distance_to(Point::new(0, 0)), None);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(a.squared_euclidean_distance(b), 25);
+        let f = Point::new(0.5f64, 0.0);
+        assert_eq!(f.squared_euclidean_distance(Point::new(0.5, 2.0)), 4.0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut p = Point::new(3, -6);
+        assert_eq!(-p, Point::new(-3, 6));
+        assert_eq!(p / 3, Point::new(1, -2));
+        assert_eq!(p.signum(), Point::new(1, -1));
+        p += Point::new(1, 1);
+        assert_eq!(p, Point::new(4, -5));
+        p -= Point::new(4, 0);
+        assert_eq!(p, Point::new(0, -5));
+        assert_eq!(
+            Point::new(1, 5).component_min(Point::new(2, 3)),
+            Point::new(1, 3)
+        );
+        assert_eq!(
+            Point::new(1, 5).component_max(Point::new(2, 3)),
+            Point::new(2, 5)
+        );
+        let points = [Point::new(1, 2), Point::new(3, 4)];
+        assert_eq!(points.iter().sum::<Point>(), Point::new(4, 6));
+        assert_eq!(Point::from((7, 8)), Point::new(7, 8));
+        assert_eq!(Point::new(1i32, 2i32).to_string(), "(1, 2)");
+    }
+
     #[test]
     fn test_line_to_y() {
         let start = Point::new(0, 0);
@@ -216,6 +453,91 @@ mod tests {
         assert_eq!(points[10], Point::new(10, 0));
     }
 
+    #[test]
+    fn test_line_to_zero_length() {
+        let p = Point::new(3, 4);
+        assert_eq!(p.line_to(p).collect::<Vec<_>>(), vec![p]);
+        assert_eq!(p.supercover_line_to(p).collect::<Vec<_>>(), vec![p]);
+    }
+
+    #[test]
+    fn test_line_to_any_angle() {
+        let points = Point::new(0, 0)
+            .line_to(Point::new(4, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(3, 2),
+                Point::new(4, 2)
+            ]
+        );
+        let points = Point::new(0, 0)
+            .line_to(Point::new(-3, 3))
+            .collect::<Vec<_>>();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[2], Point::new(-2, 2));
+    }
+
+    #[test]
+    fn test_diagonal_line_to() {
+        let points = Point::new(1, 1)
+            .diagonal_line_to(Point::new(-1, 3))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![Point::new(1, 1), Point::new(0, 2), Point::new(-1, 3)]
+        );
+        assert!(
+            Point::new(0, 0)
+                .diagonal_line_to(Point::new(0, 5))
+                .is_some()
+        );
+        assert!(
+            Point::new(0, 0)
+                .diagonal_line_to(Point::new(1, 5))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_supercover_line_to() {
+        let points = Point::new(0, 0)
+            .supercover_line_to(Point::new(2, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(1, 2),
+                Point::new(2, 2)
+            ]
+        );
+        // passes exactly through the corner at (1.5, -0.5)
+        let points = Point::new(0, 0)
+            .supercover_line_to(Point::new(3, -1))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(2, 0),
+                Point::new(1, -1),
+                Point::new(2, -1),
+                Point::new(3, -1)
+            ]
+        );
+    }
+
     #[test]
     fn test_rotate_by_cw() {
         assert_eq!(Point::new(1, 0).rotate_by(Rotation::CW), Point::new(0, 1));
@@ -235,6 +557,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rotate_by_general() {
+        assert_eq!(Point::new(2, 1).rotate_by(Rotation::CW), Point::new(-1, 2));
+        assert_eq!(Point::new(2, 1).rotate_by(Rotation::CCW), Point::new(1, -2));
+        assert_eq!(
+            Point::new(2, 1).rotate_by(Rotation::Mirror),
+            Point::new(-2, -1)
+        );
+    }
+
     #[test]
     fn test_rotate_by_mirror() {
         assert_eq!(
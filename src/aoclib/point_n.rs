@@ -0,0 +1,337 @@
+use std::fmt;
+
+use crate::dimval::DimVal;
+use crate::kd_tree::KdPoint;
+use crate::point::Point;
+use crate::vec3::Vec3;
+
+/// A point in `D` dimensions, for when [`Point`] and [`Vec3`] run out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "I: serde::Serialize",
+        deserialize = "I: serde::Deserialize<'de>"
+    ))
+)]
+pub struct PointN<I: DimVal, const D: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_coords"))]
+    pub coords: [I; D],
+}
+
+impl<I: DimVal, const D: usize> PointN<I, D> {
+    pub fn new(coords: [I; D]) -> Self {
+        Self { coords }
+    }
+
+    pub fn origin() -> Self {
+        Self::new([I::zero(); D])
+    }
+
+    /// Build a point by computing each coordinate from its axis
+    pub fn from_fn<F: FnMut(usize) -> I>(f: F) -> Self {
+        Self::new(std::array::from_fn(f))
+    }
+
+    /// The unit step along the given axis
+    pub fn unit(axis: usize) -> Self {
+        Self::from_fn(|a| if a == axis { I::one() } else { I::zero() })
+    }
+
+    fn zip_with<F: Fn(I, I) -> I>(&self, other: Self, f: F) -> Self {
+        Self::from_fn(|a| f(self.coords[a], other.coords[a]))
+    }
+
+    fn map<F: Fn(I) -> I>(&self, f: F) -> Self {
+        Self::from_fn(|a| f(self.coords[a]))
+    }
+
+    pub fn manhattan_distance(&self, other: Self) -> I {
+        (*self - other)
+            .coords
+            .into_iter()
+            .fold(I::zero(), |acc, d| acc + d.abs())
+    }
+
+    /// The number of king's moves between the two points (i.e., max of the
+    /// distances along each axis)
+    pub fn chebyshev_distance(&self, other: Self) -> I {
+        (*self - other)
+            .coords
+            .into_iter()
+            .map(|d| d.abs())
+            .fold(I::zero(), |acc, d| if d > acc { d } else { acc })
+    }
+
+    pub fn squared_euclidean_distance(&self, other: Self) -> I {
+        (*self - other)
+            .coords
+            .into_iter()
+            .fold(I::zero(), |acc, d| acc + d * d)
+    }
+
+    /// Component-wise minimum of the two points
+    pub fn component_min(&self, other: Self) -> Self {
+        self.zip_with(other, |a, b| if b < a { b } else { a })
+    }
+
+    /// Component-wise maximum of the two points
+    pub fn component_max(&self, other: Self) -> Self {
+        self.zip_with(other, |a, b| if b > a { b } else { a })
+    }
+
+    /// Component-wise sign; useful for turning an offset into a unit step
+    pub fn signum(&self) -> Self {
+        self.map(|v| v.signum())
+    }
+
+    /// The 2·D points one step away along a single axis
+    pub fn face_neighbors(&self) -> Vec<Self> {
+        (0..D)
+            .flat_map(|axis| [*self + Self::unit(axis), *self - Self::unit(axis)])
+            .collect()
+    }
+
+    /// The 3^D - 1 points differing by at most one along every axis
+    pub fn all_neighbors(&self) -> Vec<Self> {
+        let mut out = vec![*self];
+        for axis in 0..D {
+            out = out
+                .into_iter()
+                .flat_map(|p| [p - Self::unit(axis), p, p + Self::unit(axis)])
+                .collect();
+        }
+        out.retain(|p| p != self);
+        out
+    }
+}
+
+impl<I: DimVal, const D: usize> fmt::Display for PointN<I, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, c) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Index<usize> for PointN<I, D> {
+    type Output = I;
+
+    fn index(&self, axis: usize) -> &I {
+        &self.coords[axis]
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::IndexMut<usize> for PointN<I, D> {
+    fn index_mut(&mut self, axis: usize) -> &mut I {
+        &mut self.coords[axis]
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Add for PointN<I, D> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Sub for PointN<I, D> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Mul<I> for PointN<I, D> {
+    type Output = Self;
+
+    fn mul(self, other: I) -> Self {
+        self.map(|v| v * other)
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Div<I> for PointN<I, D> {
+    type Output = Self;
+
+    fn div(self, other: I) -> Self {
+        self.map(|v| v / other)
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::Neg for PointN<I, D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|v| -v)
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::AddAssign for PointN<I, D> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<I: DimVal, const D: usize> std::ops::SubAssign for PointN<I, D> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<I: DimVal, const D: usize> std::iter::Sum for PointN<I, D> {
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::origin(), |a, b| a + b)
+    }
+}
+
+impl<'a, I: DimVal, const D: usize> std::iter::Sum<&'a PointN<I, D>> for PointN<I, D> {
+    fn sum<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<I: DimVal, const D: usize> From<[I; D]> for PointN<I, D> {
+    fn from(coords: [I; D]) -> Self {
+        Self::new(coords)
+    }
+}
+
+impl<I: DimVal> From<Point<I>> for PointN<I, 2> {
+    fn from(p: Point<I>) -> Self {
+        Self::new([p.x, p.y])
+    }
+}
+
+impl<I: DimVal> From<PointN<I, 2>> for Point<I> {
+    fn from(p: PointN<I, 2>) -> Self {
+        let [x, y] = p.coords;
+        Point::new(x, y)
+    }
+}
+
+impl<I: DimVal> From<Vec3<I>> for PointN<I, 3> {
+    fn from(p: Vec3<I>) -> Self {
+        Self::new([p.x, p.y, p.z])
+    }
+}
+
+impl<I: DimVal> From<PointN<I, 3>> for Vec3<I> {
+    fn from(p: PointN<I, 3>) -> Self {
+        let [x, y, z] = p.coords;
+        Vec3::new(x, y, z)
+    }
+}
+
+impl<I: DimVal + Ord, const D: usize> KdPoint for PointN<I, D> {
+    type Coord = I;
+    const DIMENSIONS: usize = D;
+
+    fn coordinate(&self, axis: usize) -> I {
+        self.coords[axis]
+    }
+}
+
+/// serde only implements arrays up to a fixed length, so coordinates are
+/// (de)serialized as a sequence whose length is checked against `D`
+#[cfg(feature = "serde")]
+mod serde_coords {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<I: Serialize, S: Serializer, const D: usize>(
+        coords: &[I; D],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        coords.as_slice().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, I: Deserialize<'de>, De: Deserializer<'de>, const D: usize>(
+        deserializer: De,
+    ) -> Result<[I; D], De::Error> {
+        let coords = Vec::<I>::deserialize(deserializer)?;
+        let len = coords.len();
+        coords
+            .try_into()
+            .map_err(|_| De::Error::custom(format!("expected {} coordinates, got {}", D, len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PointN;
+    use crate::point::Point;
+    use crate::vec3::Vec3;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = PointN::new([1, 2, 3, 4]);
+        let b = PointN::new([4, 3, 2, 1]);
+        assert_eq!(a + b, PointN::new([5; 4]));
+        assert_eq!(a - b, PointN::new([-3, -1, 1, 3]));
+        assert_eq!(-a * 2, PointN::new([-2, -4, -6, -8]));
+        assert_eq!((a * 3) / 3, a);
+        assert_eq!(
+            [a, b, a].iter().sum::<PointN<i64, 4>>(),
+            PointN::new([6, 7, 8, 9])
+        );
+        assert_eq!(a.manhattan_distance(b), 8);
+        assert_eq!(a.chebyshev_distance(b), 3);
+        assert_eq!(a.squared_euclidean_distance(b), 20);
+        assert_eq!(a.component_min(b), PointN::new([1, 2, 2, 1]));
+        assert_eq!((b - a).signum(), PointN::new([1, 1, -1, -1]));
+        assert_eq!(a[2], 3);
+        assert_eq!(a.to_string(), "(1, 2, 3, 4)");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = PointN::<i64, 4>::origin();
+        let faces = p.face_neighbors();
+        assert_eq!(faces.len(), 8);
+        assert!(faces.iter().all(|n| n.manhattan_distance(p) == 1));
+        let all = p.all_neighbors();
+        assert_eq!(all.len(), 80);
+        assert!(all.iter().all(|n| n.chebyshev_distance(p) == 1));
+        assert_eq!(
+            all.iter().collect::<std::collections::BTreeSet<_>>().len(),
+            80
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let p: PointN<i64, 2> = Point::new(3, -4).into();
+        assert_eq!(p, PointN::new([3, -4]));
+        assert_eq!(Point::from(p), Point::new(3, -4));
+        let v: PointN<i64, 3> = Vec3::new(1, 2, 3).into();
+        assert_eq!(Vec3::from(v), Vec3::new(1, 2, 3));
+        let p = Point::new(5, 5);
+        let mut from_n = PointN::from(p)
+            .all_neighbors()
+            .into_iter()
+            .map(Point::from)
+            .collect::<Vec<_>>();
+        let mut from_2d = p.all_neighbors_array().to_vec();
+        from_n.sort();
+        from_2d.sort();
+        assert_eq!(from_n, from_2d);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let p = PointN::new([1i64, -2, 3, 4]);
+        let s = serde_json::to_string(&p).unwrap();
+        assert_eq!(s, r#"{"coords":[1,-2,3,4]}"#);
+        assert_eq!(serde_json::from_str::<PointN<i64, 4>>(&s).unwrap(), p);
+        assert!(serde_json::from_str::<PointN<i64, 3>>(&s).is_err());
+    }
+}
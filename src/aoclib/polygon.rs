@@ -0,0 +1,829 @@
+use std::collections::BTreeMap;
+use std::ops::{Range, RangeInclusive};
+
+use itertools::Itertools;
+
+use crate::point::Point;
+use crate::range_set::RangeSet;
+use crate::rect::Rect;
+
+type Index = i64;
+
+/// A simple orthogonal polygon, given as a closed loop of vertices in which
+/// each vertex shares an x or y coordinate with the next.
+///
+/// Points are treated as unit tiles: the boundary passes through the tiles
+/// between consecutive vertices, and a tile is "in" the polygon if it is on
+/// the boundary or enclosed by it.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point>,
+    bounds: Rect,
+    /// the tiles within `bounds` which are outside the polygon
+    outside: RectIndex,
+}
+
+impl RectilinearPolygon {
+    /// Building the polygon takes O(n log n) for n vertices.
+    pub fn new(vertices: Vec<Point>) -> anyhow::Result<Self> {
+        if vertices.len() < 4 {
+            anyhow::bail!("a rectilinear polygon needs at least 4 vertices");
+        }
+        for (a, b) in vertices.iter().circular_tuple_windows() {
+            if a.x != b.x && a.y != b.y {
+                anyhow::bail!("edge from {} to {} is not axis-aligned", a, b);
+            }
+        }
+        let bounds =
+            Rect::from_points(vertices.iter().copied()).expect("a polygon has at least one vertex");
+        let outside = RectIndex::new(outside_tiles(&vertices, &bounds));
+        Ok(Self {
+            vertices,
+            bounds,
+            outside,
+        })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// The geometric area enclosed by the loop through the vertices, from the
+    /// shoelace formula
+    pub fn area(&self) -> Index {
+        let twice: Index = self
+            .vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        twice.abs() / 2
+    }
+
+    /// The number of tiles on the boundary
+    pub fn boundary_points(&self) -> Index {
+        self.vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.manhattan_distance(*b))
+            .sum()
+    }
+
+    /// The number of tiles strictly inside the boundary, from Pick's theorem
+    pub fn interior_points(&self) -> Index {
+        self.area() - self.boundary_points() / 2 + 1
+    }
+
+    /// The number of tiles in the polygon, including its boundary
+    pub fn tile_count(&self) -> Index {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Is the tile on the boundary of or inside the polygon? O(log² n).
+    pub fn contains(&self, p: Point) -> bool {
+        self.contains_rect(&Rect::new(p, p))
+    }
+
+    /// Is every tile of the rectangle on the boundary of or inside the
+    /// polygon? O(log² n).
+    pub fn contains_rect(&self, rect: &Rect) -> bool {
+        self.bounds.contains_rect(rect) && !self.outside.meets(rect)
+    }
+
+    /// The largest (by tile area) rectangle with two opposite corners on
+    /// vertices which fits entirely in the polygon.
+    ///
+    /// Rather than trying every pair of vertices, this splits them in half by
+    /// x, then splits the pairs across that line in half by y, so each pair
+    /// is considered around the one point where both lines cross its
+    /// rectangle. See [`largest_around`] for how the pairs around a point are
+    /// searched; the whole search is O(n log⁵ n).
+    pub fn largest_inscribed_rect(&self) -> Option<Rect> {
+        let corners = self.vertices.iter().copied().sorted().dedup().collect_vec();
+        // rectangles from a lower-left to an upper-right corner, then (with y
+        // flipped) from an upper-left to a lower-right one
+        [1, -1]
+            .into_iter()
+            .filter_map(|sign| {
+                let flip = |p: Point| Point::new(p.x, sign * p.y);
+                let points = corners.iter().map(|p| flip(*p)).sorted().collect_vec();
+                let fits = |r: &Rect| self.contains_rect(&Rect::new(flip(r.min), flip(r.max)));
+                largest_between(&points, &fits).map(|(a, b)| Rect::new(flip(a), flip(b)))
+            })
+            .max_by_key(|r| r.area())
+    }
+}
+
+/// The tiles within `bounds` which are outside the polygon, as rectangles
+/// which don't overlap.
+///
+/// This sweeps from left to right across the columns holding vertical edges,
+/// tracking the horizontal edges which cross the columns in between. A tile
+/// can only be in or out differently from its neighbors to the left and
+/// right if it is on one of those vertical edges, so only the runs of outside
+/// tiles next to them need revisiting; every other run carries on the
+/// rectangle it started. Each edge touches O(1) runs, so there are O(n)
+/// rectangles.
+fn outside_tiles(vertices: &[Point], bounds: &Rect) -> Vec<Rect> {
+    // only the corners matter, not repeated or collinear vertices
+    let mut looped = vertices.iter().copied().dedup().collect_vec();
+    if looped.len() > 1 && looped.first() == looped.last() {
+        looped.pop();
+    }
+    let corners = looped
+        .iter()
+        .circular_tuple_windows()
+        .filter(|(a, b, c)| !((a.x == b.x && b.x == c.x) || (a.y == b.y && b.y == c.y)))
+        .map(|(_, b, _)| *b)
+        .collect_vec();
+    let twice_area: Index = corners
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+    let mut verticals: BTreeMap<Index, Vec<RangeInclusive<Index>>> = BTreeMap::new();
+    // the horizontal edges starting and ending at each x; those starting note
+    // whether the interior is above them (at greater y)
+    let mut starting: BTreeMap<Index, Vec<(Index, bool)>> = BTreeMap::new();
+    let mut ending: BTreeMap<Index, Vec<Index>> = BTreeMap::new();
+    for (a, b) in corners.iter().circular_tuple_windows() {
+        if a.x == b.x {
+            verticals
+                .entry(a.x)
+                .or_default()
+                .push(a.y.min(b.y)..=a.y.max(b.y));
+        } else {
+            // the interior is to the left of a counter-clockwise loop
+            let interior_above = (b.x > a.x) == (twice_area > 0);
+            starting
+                .entry(a.x.min(b.x))
+                .or_default()
+                .push((a.y, interior_above));
+            ending.entry(a.x.max(b.x)).or_default().push(a.y);
+        }
+    }
+
+    let mut crossing: BTreeMap<Index, bool> = BTreeMap::new();
+    // runs of outside tiles in the current column, by their lowest y, with
+    // their highest y and the column where they began
+    let mut runs = BTreeMap::from([(bounds.min.y, (bounds.max.y, bounds.min.x))]);
+    let mut out = vec![];
+    for (&x, edges) in &verticals {
+        let windows = windows_around(edges, &runs, bounds);
+        let before = windows
+            .iter()
+            .map(|w| outside_between(&crossing, w))
+            .collect_vec();
+        for y in ending.get(&x).into_iter().flatten() {
+            crossing.remove(y);
+        }
+        for (y, interior_above) in starting.get(&x).into_iter().flatten() {
+            crossing.insert(*y, *interior_above);
+        }
+        let on_edges = edges.iter().cloned().collect::<RangeSet<_>>();
+        for (window, before) in windows.iter().zip(before) {
+            let after = outside_between(&crossing, window);
+            let at = before.intersection(&after).difference(&on_edges);
+            replace_runs(&mut runs, &mut out, window, &at, x);
+            replace_runs(&mut runs, &mut out, window, &after, x + 1);
+        }
+    }
+    out.extend(
+        runs.into_iter()
+            .filter(|(_, (_, start))| *start <= bounds.max.x)
+            .map(|(lo, (hi, start))| {
+                Rect::new(Point::new(start, lo), Point::new(bounds.max.x, hi))
+            }),
+    );
+    out
+}
+
+/// The rows which may change at a column of vertical edges: each edge with a
+/// row of margin on either side, widened to take in whole runs of outside
+/// tiles
+fn windows_around(
+    edges: &[RangeInclusive<Index>],
+    runs: &BTreeMap<Index, (Index, Index)>,
+    bounds: &Rect,
+) -> Vec<RangeInclusive<Index>> {
+    let run_at = |y: Index| {
+        runs.range(..=y)
+            .next_back()
+            .filter(|(_, (hi, _))| *hi >= y)
+            .map(|(lo, (hi, _))| (*lo, *hi))
+    };
+    edges
+        .iter()
+        .map(|e| {
+            let lo = (e.start() - 1).max(bounds.min.y);
+            let hi = (e.end() + 1).min(bounds.max.y);
+            run_at(lo).map_or(lo, |r| r.0)..=run_at(hi).map_or(hi, |r| r.1)
+        })
+        .sorted_by_key(|w| *w.start())
+        .coalesce(|a, b| {
+            if *b.start() <= a.end() + 1 {
+                Ok(*a.start()..=*a.end().max(b.end()))
+            } else {
+                Err((a, b))
+            }
+        })
+        .collect()
+}
+
+/// The outside tiles within `window` in a column between vertical edges,
+/// given the horizontal edges crossing it
+fn outside_between(
+    crossing: &BTreeMap<Index, bool>,
+    window: &RangeInclusive<Index>,
+) -> RangeSet<Index> {
+    let mut outside = RangeSet::from_iter([window.clone()]);
+    let from = crossing
+        .range(..=*window.start())
+        .next_back()
+        .map_or(*window.start(), |(y, _)| *y);
+    let mut bottom = None;
+    for (&y, &interior_above) in crossing.range(from..) {
+        if interior_above {
+            if y > *window.end() {
+                break;
+            }
+            bottom = Some(y);
+        } else {
+            outside.remove(bottom.take().unwrap_or(y)..=y);
+            if y >= *window.end() {
+                break;
+            }
+        }
+    }
+    outside
+}
+
+/// Replace the runs within `window` with those in `now`, starting at column
+/// `x`. Runs which haven't changed carry on; the rest are finished off as
+/// rectangles in `out`.
+fn replace_runs(
+    runs: &mut BTreeMap<Index, (Index, Index)>,
+    out: &mut Vec<Rect>,
+    window: &RangeInclusive<Index>,
+    now: &RangeSet<Index>,
+    x: Index,
+) {
+    let old = runs
+        .range(window.clone())
+        .map(|(lo, (hi, start))| (*lo, *hi, *start))
+        .collect_vec();
+    for (lo, hi, start) in old {
+        if now.range_containing(lo) != Some(lo..=hi) {
+            runs.remove(&lo);
+            if start < x {
+                out.push(Rect::new(Point::new(start, lo), Point::new(x - 1, hi)));
+            }
+        }
+    }
+    for r in now.iter() {
+        runs.entry(*r.start()).or_insert((*r.end(), x));
+    }
+}
+
+/// Rectangles which don't overlap, indexed to find whether any of them meets
+/// a given rectangle in O(log² n). Those which meet it either span its left
+/// column or start to the right of that, within it.
+#[derive(Debug, Clone)]
+struct RectIndex {
+    /// the boundaries between columns of the segment tree's leaves
+    xs: Vec<Index>,
+    /// for each node of a segment tree over `xs`, the y ranges of the
+    /// rectangles spanning all of its columns, which can't overlap
+    spanning: Vec<Vec<(Index, Index)>>,
+    /// every rectangle's lowest x, in order
+    lefts: Vec<Index>,
+    /// for each node of a segment tree over `lefts`, its rectangles' y ranges
+    /// ordered by lowest y, with the running maximum of the highest y
+    by_left: Vec<Vec<(Index, Index)>>,
+}
+
+impl RectIndex {
+    fn new(mut rects: Vec<Rect>) -> Self {
+        let xs = rects
+            .iter()
+            .flat_map(|r| [r.min.x, r.max.x + 1])
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let leaves = xs.len().next_power_of_two();
+        let mut spanning = vec![vec![]; 2 * leaves];
+        for r in &rects {
+            let column = |x| xs.binary_search(&x).unwrap() + leaves;
+            let (mut lo, mut hi) = (column(r.min.x), column(r.max.x + 1));
+            while lo < hi {
+                if lo % 2 == 1 {
+                    spanning[lo].push((r.min.y, r.max.y));
+                    lo += 1;
+                }
+                if hi % 2 == 1 {
+                    hi -= 1;
+                    spanning[hi].push((r.min.y, r.max.y));
+                }
+                lo /= 2;
+                hi /= 2;
+            }
+        }
+        for ys in &mut spanning {
+            ys.sort();
+        }
+
+        rects.sort_by_key(|r| r.min.x);
+        let size = rects.len().next_power_of_two();
+        let mut by_left = vec![vec![]; 2 * size];
+        for (i, r) in rects.iter().enumerate() {
+            by_left[size + i].push((r.min.y, r.max.y));
+        }
+        for node in (1..size).rev() {
+            by_left[node] = by_left[2 * node]
+                .iter()
+                .chain(&by_left[2 * node + 1])
+                .copied()
+                .sorted()
+                .collect();
+        }
+        for ys in &mut by_left {
+            let mut highest = Index::MIN;
+            for (_, hi) in ys.iter_mut() {
+                highest = highest.max(*hi);
+                *hi = highest;
+            }
+        }
+        Self {
+            xs,
+            spanning,
+            lefts: rects.iter().map(|r| r.min.x).collect(),
+            by_left,
+        }
+    }
+
+    fn meets(&self, r: &Rect) -> bool {
+        self.spanning_meets(r.min.x, r.min.y, r.max.y) || self.starting_meets(r)
+    }
+
+    fn spanning_meets(&self, x: Index, lo: Index, hi: Index) -> bool {
+        let leaf = self.xs.partition_point(|v| *v <= x);
+        if leaf == 0 || leaf == self.xs.len() {
+            return false;
+        }
+        let mut node = leaf - 1 + self.spanning.len() / 2;
+        while node > 0 {
+            let ys = &self.spanning[node];
+            let i = ys.partition_point(|(_, y)| *y < lo);
+            if ys.get(i).is_some_and(|(y, _)| *y <= hi) {
+                return true;
+            }
+            node /= 2;
+        }
+        false
+    }
+
+    fn starting_meets(&self, r: &Rect) -> bool {
+        let meets = |node: usize| {
+            let ys = &self.by_left[node];
+            let i = ys.partition_point(|(y, _)| *y <= r.max.y);
+            i > 0 && ys[i - 1].1 >= r.min.y
+        };
+        let size = self.by_left.len() / 2;
+        let mut lo = self.lefts.partition_point(|x| *x <= r.min.x) + size;
+        let mut hi = self.lefts.partition_point(|x| *x <= r.max.x) + size;
+        while lo < hi {
+            if lo % 2 == 1 {
+                if meets(lo) {
+                    return true;
+                }
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                if meets(hi) {
+                    return true;
+                }
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+        false
+    }
+}
+
+/// A candidate rectangle, as its lower-left and upper-right corners
+type Corners = (Point, Point);
+
+fn largest<It: IntoIterator<Item = Option<Corners>>>(candidates: It) -> Option<Corners> {
+    candidates
+        .into_iter()
+        .flatten()
+        .max_by_key(|(a, b)| Rect::new(*a, *b).area())
+}
+
+/// The largest rectangle which `fits` from one of `points` (sorted) to
+/// another above and right of it
+fn largest_between(points: &[Point], fits: &impl Fn(&Rect) -> bool) -> Option<Corners> {
+    if points.len() < 2 {
+        return None;
+    }
+    let (left, right) = points.split_at(points.len() / 2);
+    let x = left[left.len() - 1].x;
+    // lower-left corners go first on ties, so they pair with anything level
+    let ends = left
+        .iter()
+        .map(|p| (*p, false))
+        .chain(right.iter().map(|p| (*p, true)))
+        .sorted_by_key(|(p, high)| (p.y, *high))
+        .collect_vec();
+    largest([
+        largest_between(left, fits),
+        largest_between(right, fits),
+        largest_across(&ends, x, fits),
+    ])
+}
+
+/// The largest rectangle which `fits` from a lower-left corner in `ends`
+/// (sorted by y, and flagged false) at or left of column `x` to an upper-right
+/// corner above it at or right of `x`
+fn largest_across(
+    ends: &[(Point, bool)],
+    x: Index,
+    fits: &impl Fn(&Rect) -> bool,
+) -> Option<Corners> {
+    if ends.len() < 2 {
+        return None;
+    }
+    let (below, above) = ends.split_at(ends.len() / 2);
+    let center = Point::new(x, below[below.len() - 1].0.y);
+    let lows = below
+        .iter()
+        .filter(|(_, high)| !high)
+        .map(|(p, _)| *p)
+        .collect_vec();
+    let highs = above
+        .iter()
+        .filter(|(_, high)| *high)
+        .map(|(p, _)| *p)
+        .collect_vec();
+    largest([
+        largest_across(below, x, fits),
+        largest_across(above, x, fits),
+        largest_around(&lows, &highs, center, fits),
+    ])
+}
+
+/// An upper-right corner, and the least x and y of a lower-left corner which
+/// makes a rectangle that fits with it
+#[derive(Debug, Clone, Copy)]
+struct Reach {
+    high: Point,
+    min: Point,
+}
+
+/// The largest rectangle which `fits` from one of `lows` to one of `highs`,
+/// each of which encloses `center`.
+///
+/// Such a rectangle fits exactly when its four quarters about the center do.
+/// The lower-left quarter only depends on the low corner and the upper-right
+/// one on the high corner; the other two each shrink as the low corner moves
+/// left or down, so they bound how far it can go for a given high corner.
+fn largest_around(
+    lows: &[Point],
+    highs: &[Point],
+    center: Point,
+    fits: &impl Fn(&Rect) -> bool,
+) -> Option<Corners> {
+    if lows.len() * highs.len() <= 64 {
+        return largest(
+            lows.iter()
+                .cartesian_product(highs)
+                .filter(|(a, b)| fits(&Rect::new(**a, **b)))
+                .map(|(a, b)| Some((*a, *b))),
+        );
+    }
+    let lows_by_x = lows
+        .iter()
+        .copied()
+        .filter(|a| fits(&Rect::new(*a, center)))
+        .sorted()
+        .collect_vec();
+    let lows_by_y = lows_by_x
+        .iter()
+        .copied()
+        .sorted_by_key(|a| (a.y, a.x))
+        .collect_vec();
+    let reaches = highs
+        .iter()
+        .filter(|b| fits(&Rect::new(center, **b)))
+        .filter_map(|b| {
+            let upper_left = |a: &Point| {
+                fits(&Rect::new(
+                    Point::new(a.x, center.y),
+                    Point::new(center.x, b.y),
+                ))
+            };
+            let lower_right = |a: &Point| {
+                fits(&Rect::new(
+                    Point::new(center.x, a.y),
+                    Point::new(b.x, center.y),
+                ))
+            };
+            let x = lows_by_x
+                .get(lows_by_x.partition_point(|a| !upper_left(a)))?
+                .x;
+            let y = lows_by_y
+                .get(lows_by_y.partition_point(|a| !lower_right(a)))?
+                .y;
+            Some(Reach {
+                high: *b,
+                min: Point::new(x, y),
+            })
+        })
+        .collect_vec();
+    largest_in_reach(&lows_by_x, &reaches, |p| p.x, &|lows, reaches| {
+        let lows = lows
+            .iter()
+            .copied()
+            .sorted_by_key(|a| (a.y, a.x))
+            .collect_vec();
+        largest_in_reach(&lows, reaches, |p| p.y, &largest_pairing)
+    })
+}
+
+/// Finds the largest rectangle from some low corners to some reaches
+type Pairing = dyn Fn(&[Point], &[Reach]) -> Option<Corners>;
+
+/// Split `lows` (sorted by `key`) like a segment tree, and pass each of the
+/// largest pieces wholly within some reaches to `then` along with those
+/// reaches. Each reach ends up with O(log n) pieces.
+fn largest_in_reach(
+    lows: &[Point],
+    reaches: &[Reach],
+    key: fn(&Point) -> Index,
+    then: &Pairing,
+) -> Option<Corners> {
+    let (Some(first), Some(last)) = (lows.first(), lows.last()) else {
+        return None;
+    };
+    let (whole, part): (Vec<_>, Vec<_>) = reaches
+        .iter()
+        .filter(|r| key(&r.min) <= key(last))
+        .partition(|r| key(&r.min) <= key(first));
+    let here = if whole.is_empty() {
+        None
+    } else {
+        then(lows, &whole)
+    };
+    if part.is_empty() {
+        return here;
+    }
+    let (left, right) = lows.split_at(lows.len() / 2);
+    largest([
+        here,
+        largest_in_reach(left, &part, key, then),
+        largest_in_reach(right, &part, key, then),
+    ])
+}
+
+/// The largest rectangle from any of `lows` to any of the reaches' high
+/// corners, when every pairing fits.
+///
+/// Only the lower-left staircase of low corners and the upper-right staircase
+/// of high ones can give the largest rectangle. Along those the areas form a
+/// Monge array, so the best high corner for each low corner moves
+/// monotonically and divide and conquer finds it in O(n log n).
+fn largest_pairing(lows: &[Point], reaches: &[Reach]) -> Option<Corners> {
+    let lows = lows.iter().copied().sorted().fold(vec![], |mut stair, a| {
+        if stair.last().is_none_or(|s: &Point| a.y < s.y) {
+            stair.push(a);
+        }
+        stair
+    });
+    let mut highs = reaches
+        .iter()
+        .map(|r| r.high)
+        .sorted()
+        .rev()
+        .fold(vec![], |mut stair, b| {
+            if stair.last().is_none_or(|s: &Point| b.y > s.y) {
+                stair.push(b);
+            }
+            stair
+        });
+    highs.reverse();
+    largest_monotone(&lows, &highs, 0..lows.len(), 0..highs.len())
+}
+
+fn largest_monotone(
+    lows: &[Point],
+    highs: &[Point],
+    rows: Range<usize>,
+    columns: Range<usize>,
+) -> Option<Corners> {
+    if rows.is_empty() {
+        return None;
+    }
+    let row = (rows.start + rows.end) / 2;
+    let a = lows[row];
+    // the leftmost of the best, so neither half misses its own best
+    let column = columns
+        .clone()
+        .rev()
+        .max_by_key(|j| Rect::new(a, highs[*j]).area())?;
+    largest([
+        Some((a, highs[column])),
+        largest_monotone(lows, highs, rows.start..row, columns.start..column + 1),
+        largest_monotone(lows, highs, row + 1..rows.end, column..columns.end),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+
+    use super::{Point, Rect, RectilinearPolygon};
+
+    fn sample() -> RectilinearPolygon {
+        RectilinearPolygon::new(vec![
+            Point::new(7, 1),
+            Point::new(11, 1),
+            Point::new(11, 7),
+            Point::new(9, 7),
+            Point::new(9, 5),
+            Point::new(2, 5),
+            Point::new(2, 3),
+            Point::new(7, 3),
+        ])
+        .unwrap()
+    }
+
+    /// Check every tile near the polygon and the largest rectangle against
+    /// ray casting, in both directions around the loop
+    fn check_brute_force(vertices: Vec<Point>) {
+        let edges = vertices
+            .iter()
+            .copied()
+            .circular_tuple_windows()
+            .collect_vec();
+        let inside = |t: Point| {
+            edges.iter().any(|(a, b)| Rect::new(*a, *b).contains(t))
+                || edges
+                    .iter()
+                    .filter(|(a, b)| a.x == b.x && a.x > t.x)
+                    .filter(|(a, b)| a.y.min(b.y) <= t.y && t.y < a.y.max(b.y))
+                    .count()
+                    % 2
+                    == 1
+        };
+        let bounds = Rect::from_points(vertices.iter().copied()).unwrap();
+        let tiles = Rect::new(bounds.min - Point::new(1, 1), bounds.max + Point::new(1, 1))
+            .points()
+            .filter(|t| inside(*t))
+            .collect::<HashSet<_>>();
+        let largest = vertices
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| Rect::new(*a, *b))
+            .filter(|r| r.points().all(|t| tiles.contains(&t)))
+            .map(|r| r.area())
+            .max();
+        for order in [vertices.clone(), vertices.into_iter().rev().collect()] {
+            let p = RectilinearPolygon::new(order).unwrap();
+            for t in
+                Rect::new(bounds.min - Point::new(1, 1), bounds.max + Point::new(1, 1)).points()
+            {
+                assert_eq!(p.contains(t), tiles.contains(&t), "{}", t);
+            }
+            assert_eq!(p.tile_count(), tiles.len() as i64);
+            assert_eq!(p.largest_inscribed_rect().map(|r| r.area()), largest);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(
+            RectilinearPolygon::new(vec![
+                Point::new(0, 0),
+                Point::new(2, 0),
+                Point::new(2, 2),
+                Point::new(1, 3)
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_measurements() {
+        let square = RectilinearPolygon::new(vec![
+            Point::new(0, 0),
+            Point::new(3, 0),
+            Point::new(3, 3),
+            Point::new(0, 3),
+        ])
+        .unwrap();
+        assert_eq!(square.area(), 9);
+        assert_eq!(square.boundary_points(), 12);
+        assert_eq!(square.interior_points(), 4);
+        assert_eq!(square.tile_count(), 16);
+        let p = sample();
+        assert_eq!(p.tile_count(), 46);
+        let bounds = Rect::from_points(p.vertices().iter().copied()).unwrap();
+        assert_eq!(bounds.points().filter(|t| p.contains(*t)).count(), 46);
+    }
+
+    #[test]
+    fn test_contains() {
+        let p = sample();
+        assert!(p.contains(Point::new(7, 1)));
+        assert!(p.contains(Point::new(8, 2)));
+        assert!(p.contains(Point::new(3, 4)));
+        assert!(!p.contains(Point::new(3, 2)));
+        assert!(!p.contains(Point::new(8, 6)));
+        assert!(!p.contains(Point::new(0, 0)));
+        assert!(p.contains_rect(&Rect::new(Point::new(9, 5), Point::new(2, 3))));
+        assert!(!p.contains_rect(&Rect::new(Point::new(2, 5), Point::new(11, 1))));
+    }
+
+    #[test]
+    fn test_zero_width_slot() {
+        // a U shape whose arms are adjacent columns, so the slot between them
+        // contains no tiles
+        let p = RectilinearPolygon::new(vec![
+            Point::new(0, 0),
+            Point::new(1, 0),
+            Point::new(1, 5),
+            Point::new(2, 5),
+            Point::new(2, 0),
+            Point::new(3, 0),
+            Point::new(3, 6),
+            Point::new(0, 6),
+        ])
+        .unwrap();
+        assert!(p.contains_rect(&Rect::new(Point::new(0, 0), Point::new(3, 6))));
+        assert_eq!(p.tile_count(), 28);
+    }
+
+    #[test]
+    fn test_enclosed_pocket() {
+        // the pocket in the middle is outside, but only reached from the rest
+        // of the outside through a slot with no tiles in it
+        let vertices = vec![
+            Point::new(0, 0),
+            Point::new(10, 0),
+            Point::new(10, 10),
+            Point::new(6, 10),
+            Point::new(6, 7),
+            Point::new(7, 7),
+            Point::new(7, 3),
+            Point::new(3, 3),
+            Point::new(3, 7),
+            Point::new(5, 7),
+            Point::new(5, 10),
+            Point::new(0, 10),
+        ];
+        let p = RectilinearPolygon::new(vertices.clone()).unwrap();
+        assert!(!p.contains(Point::new(5, 5)));
+        assert!(p.contains(Point::new(5, 8)));
+        check_brute_force(vertices);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let vertices = vec![
+            Point::new(7, 1),
+            Point::new(11, 1),
+            Point::new(11, 7),
+            Point::new(9, 7),
+            Point::new(9, 5),
+            Point::new(2, 5),
+            Point::new(2, 3),
+            Point::new(7, 3),
+        ];
+        check_brute_force(vertices);
+        // ragged top and bottom edges, with a collinear vertex on each step
+        let heights = |i: i64| [(i * 7 % 11) + 1, -(i * 5 % 9) - 1];
+        let top = (0..12).flat_map(|i| {
+            let h = heights(i)[0];
+            [
+                Point::new(3 * i, h),
+                Point::new(3 * i + 1, h),
+                Point::new(3 * i + 3, h),
+            ]
+        });
+        let bottom = (0..12).rev().flat_map(|i| {
+            let h = heights(i)[1];
+            [Point::new(3 * i + 3, h), Point::new(3 * i, h)]
+        });
+        check_brute_force(top.chain(bottom).collect());
+    }
+
+    #[test]
+    fn test_largest_inscribed_rect() {
+        let p = sample();
+        let r = p.largest_inscribed_rect().unwrap();
+        assert_eq!(r.area(), 24);
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use num_traits::PrimInt;
+
+/// A set of integers, stored as a sorted list of disjoint inclusive ranges.
+/// Overlapping and adjacent ranges are coalesced on insertion.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct RangeSet<T: PrimInt> {
+    /// start -> end (inclusive)
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    pub fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of disjoint ranges
+    pub fn num_ranges(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// The total number of values covered by the set, or None if that
+    /// doesn't fit in a `T` (e.g. the full range of the type)
+    pub fn covered_len(&self) -> Option<T> {
+        self.ranges.iter().try_fold(T::zero(), |acc, (s, e)| {
+            acc.checked_add(&e.checked_sub(s)?.checked_add(&T::one())?)
+        })
+    }
+
+    /// O(log n)
+    pub fn contains(&self, value: T) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    /// The (coalesced) range containing the value, if any
+    pub fn range_containing(&self, value: T) -> Option<RangeInclusive<T>> {
+        let (start, end) = self.ranges.range(..=value).next_back()?;
+        if *end >= value {
+            Some(*start..=*end)
+        } else {
+            None
+        }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // anything starting at or before end + 1 and ending at or after start - 1
+        // overlaps or is adjacent
+        let touching = self
+            .ranges
+            .range(..=end.saturating_add(T::one()))
+            .rev()
+            .take_while(|(_, e)| **e >= start.saturating_sub(T::one()))
+            .map(|(s, e)| (*s, *e))
+            .collect::<Vec<_>>();
+        for (s, e) in touching {
+            self.ranges.remove(&s);
+            start = start.min(s);
+            end = end.max(e);
+        }
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let overlapping = self
+            .ranges
+            .range(..=end)
+            .rev()
+            .take_while(|(_, e)| **e >= start)
+            .map(|(s, e)| (*s, *e))
+            .collect::<Vec<_>>();
+        for (s, e) in overlapping {
+            self.ranges.remove(&s);
+            if s < start {
+                self.ranges.insert(s, start - T::one());
+            }
+            if e > end {
+                self.ranges.insert(end + T::one(), e);
+            }
+        }
+    }
+
+    /// Iterate over the disjoint ranges in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(s, e)| *s..=*e)
+    }
+
+    /// Iterate over the gaps between consecutive ranges in increasing order
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .iter()
+            .zip(self.ranges.iter().skip(1))
+            .map(|((_, e), (s, _))| *e + T::one()..=*s - T::one())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        out.extend(other.iter());
+        out
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut out = Self::new();
+        let mut lhs = self.iter().peekable();
+        let mut rhs = other.iter().peekable();
+        while let (Some(l), Some(r)) = (lhs.peek(), rhs.peek()) {
+            let start = *l.start().max(r.start());
+            let end = *l.end().min(r.end());
+            if start <= end {
+                out.ranges.insert(start, end);
+            }
+            if l.end() < r.end() {
+                lhs.next();
+            } else {
+                rhs.next();
+            }
+        }
+        out
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for r in other.iter() {
+            out.remove(r);
+        }
+        out
+    }
+}
+
+impl<T: PrimInt> Extend<RangeInclusive<T>> for RangeSet<T> {
+    fn extend<It: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: It) {
+        for r in iter {
+            self.insert(r);
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<It: IntoIterator<Item = RangeInclusive<T>>>(iter: It) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<T: PrimInt + fmt::Debug> fmt::Debug for RangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeSet;
+
+    #[test]
+    fn test_insert_coalesces() {
+        let mut s = RangeSet::new();
+        s.insert(10u64..=14);
+        s.insert(3..=5);
+        s.insert(16..=20);
+        s.insert(12..=18);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![3..=5, 10..=20]);
+        s.insert(6..=9);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![3..=20]);
+        assert_eq!(s.covered_len(), Some(18));
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = [3u64..=5, 10..=14, 16..=20, 12..=18]
+            .into_iter()
+            .collect::<RangeSet<_>>();
+        let fresh = [1, 5, 8, 11, 17, 32]
+            .into_iter()
+            .filter(|i| s.contains(*i))
+            .collect::<Vec<_>>();
+        assert_eq!(fresh, vec![5, 11, 17]);
+        assert_eq!(s.range_containing(11), Some(10..=20));
+        assert_eq!(s.covered_len(), Some(14));
+    }
+
+    #[test]
+    fn test_remove_and_gaps() {
+        let mut s = [0i32..=100].into_iter().collect::<RangeSet<_>>();
+        s.remove(10..=19);
+        s.remove(50..=50);
+        s.remove(95..=200);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0..=9, 20..=49, 51..=94]);
+        assert_eq!(s.gaps().collect::<Vec<_>>(), vec![10..=19, 50..=50]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = [0i64..=10, 20..=30].into_iter().collect::<RangeSet<_>>();
+        let b = [5i64..=25].into_iter().collect::<RangeSet<_>>();
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![0..=30]);
+        assert_eq!(
+            a.intersection(&b).iter().collect::<Vec<_>>(),
+            vec![5..=10, 20..=25]
+        );
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            vec![0..=4, 26..=30]
+        );
+    }
+
+    #[test]
+    fn test_extremes() {
+        let mut s = RangeSet::new();
+        s.insert(250u8..=255);
+        s.insert(0..=3);
+        s.insert(4..=249);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0..=255]);
+        assert_eq!(s.covered_len(), None);
+        s.remove(0..=0);
+        assert_eq!(s.covered_len(), Some(255));
+        s.remove(255..=255);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1..=254]);
+        let s = [i8::MIN..=i8::MAX].into_iter().collect::<RangeSet<_>>();
+        assert_eq!(s.covered_len(), None);
+    }
+}
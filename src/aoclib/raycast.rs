@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::grid::DenseGrid;
+use super::point::Point;
+
+type Index = i64;
+
+/// Iterator over the cells along a ray; see [`DenseGrid::ray`]
+#[derive(Debug)]
+pub struct Ray<'a, V: Clone + fmt::Debug> {
+    grid: &'a DenseGrid<V>,
+    current: Point<Index>,
+    step: Point<Index>,
+}
+
+impl<'a, V: Clone + fmt::Debug> Iterator for Ray<'a, V> {
+    type Item = (Point<Index>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.current + self.step;
+        let value = self.grid.get_ref(next)?;
+        self.current = next;
+        Some((next, value))
+    }
+}
+
+impl<'a, V: Clone + fmt::Debug> std::iter::FusedIterator for Ray<'a, V> {}
+
+impl<V: Clone + fmt::Debug> DenseGrid<V> {
+    /// Walk from `start` in increments of `step` until leaving the grid. The
+    /// start point itself is not included.
+    pub fn ray(&self, start: Point<Index>, step: Point<Index>) -> Ray<'_, V> {
+        assert!(step != Point::new(0, 0), "ray step must be non-zero");
+        Ray {
+            grid: self,
+            current: start,
+            step,
+        }
+    }
+
+    /// Walk from `start` in increments of `step` and return the first cell for
+    /// which `stop` returns true, or None if the ray leaves the grid first.
+    pub fn cast_ray<F>(
+        &self,
+        start: Point<Index>,
+        step: Point<Index>,
+        stop: F,
+    ) -> Option<(Point<Index>, &V)>
+    where
+        F: Fn(Point<Index>, &V) -> bool,
+    {
+        self.ray(start, step).find(|(p, v)| stop(*p, v))
+    }
+
+    /// The first cell for which `stop` returns true in each of the 8
+    /// directions from `start`, in the same order as [`Point::all_neighbors_array`]
+    pub fn first_seen_in_directions<F>(
+        &self,
+        start: Point<Index>,
+        stop: F,
+    ) -> [Option<Point<Index>>; 8]
+    where
+        F: Fn(Point<Index>, &V) -> bool,
+    {
+        Point::new(0, 0)
+            .all_neighbors_array()
+            .map(|step| self.cast_ray(start, step, &stop).map(|(p, _)| p))
+    }
+
+    /// All cells visible from `start`. A cell is visible if no opaque cell lies
+    /// exactly on the straight line between it and `start`; opaque cells
+    /// themselves can be seen, but nothing behind them. The start cell is
+    /// not included.
+    pub fn visible_from<F>(&self, start: Point<Index>, opaque: F) -> BTreeSet<Point<Index>>
+    where
+        F: Fn(Point<Index>, &V) -> bool,
+    {
+        let mut visible = BTreeSet::new();
+        let mut directions = BTreeSet::new();
+        for (p, _) in self.iter() {
+            let delta = p - start;
+            if delta == Point::new(0, 0) {
+                continue;
+            }
+            let divisor = num_integer::gcd(delta.x, delta.y);
+            directions.insert(Point::new(delta.x / divisor, delta.y / divisor));
+        }
+        for step in directions {
+            for (p, v) in self.ray(start, step) {
+                visible.insert(p);
+                if opaque(p, v) {
+                    break;
+                }
+            }
+        }
+        visible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DenseGrid, Point};
+
+    #[test]
+    fn test_cast_ray() {
+        let g = DenseGrid::from_input("..S..\n.....\n..^..\n.....", |c| c);
+        let start = g.find(&'S').unwrap();
+        assert_eq!(
+            g.cast_ray(start, Point::new(0, 1), |_, c| *c != '.'),
+            Some((Point::new(2, 2), &'^'))
+        );
+        assert_eq!(g.cast_ray(start, Point::new(1, 0), |_, c| *c != '.'), None);
+        assert_eq!(g.ray(start, Point::new(-1, 1)).count(), 2);
+    }
+
+    #[test]
+    fn test_first_seen_in_directions() {
+        let g = DenseGrid::from_input("#.#\n...\n#.#", |c| c == '#');
+        let seen = g.first_seen_in_directions(Point::new(1, 1), |_, v| *v);
+        assert_eq!(
+            seen,
+            [
+                None,
+                None,
+                None,
+                None,
+                Some(Point::new(2, 2)),
+                Some(Point::new(2, 0)),
+                Some(Point::new(0, 2)),
+                Some(Point::new(0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visible_from() {
+        let g = DenseGrid::from_input(".....\n.#...\n.....\n...#.", |c| c == '#');
+        let visible = g.visible_from(Point::new(0, 0), |_, v| *v);
+        assert!(visible.contains(&Point::new(1, 1)));
+        assert!(!visible.contains(&Point::new(2, 2)));
+        assert!(!visible.contains(&Point::new(3, 3)));
+        assert!(visible.contains(&Point::new(2, 1)));
+        assert!(visible.contains(&Point::new(3, 2)));
+        assert_eq!(visible.len(), 19 - 2);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::dimval::DimVal;
+use crate::point::Point;
+use std::fmt;
+
+/// An axis-aligned rectangle, inclusive of both corners (so a rectangle whose
+/// corners are the same point has width, height, and area 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect<I: DimVal = i64> {
+    pub min: Point<I>,
+    pub max: Point<I>,
+}
+
+impl<I: DimVal> Rect<I> {
+    /// Build a rectangle from any two opposite corners
+    pub fn new(p1: Point<I>, p2: Point<I>) -> Self {
+        Self {
+            min: p1.component_min(p2),
+            max: p1.component_max(p2),
+        }
+    }
+
+    /// The bounding box of the given points, or None if there are none
+    pub fn from_points<It: IntoIterator<Item = Point<I>>>(points: It) -> Option<Self> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+        Some(iter.fold(Self::new(first, first), |r, p| r.expand_to(p)))
+    }
+
+    pub fn width(&self) -> I {
+        self.max.x - self.min.x + I::one()
+    }
+
+    pub fn height(&self) -> I {
+        self.max.y - self.min.y + I::one()
+    }
+
+    pub fn area(&self) -> I {
+        self.width() * self.height()
+    }
+
+    pub fn corners(&self) -> [Point<I>; 4] {
+        [
+            self.min,
+            Point::new(self.max.x, self.min.y),
+            self.max,
+            Point::new(self.min.x, self.max.y),
+        ]
+    }
+
+    pub fn contains(&self, p: Point<I>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Is the point strictly inside the rectangle (i.e., not on its border)?
+    pub fn contains_in_interior(&self, p: Point<I>) -> bool {
+        p.x > self.min.x && p.x < self.max.x && p.y > self.min.y && p.y < self.max.y
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.component_max(other.min);
+        let max = self.max.component_min(other.max);
+        if min.x > max.x || min.y > max.y {
+            None
+        } else {
+            Some(Self { min, max })
+        }
+    }
+
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.component_min(other.min),
+            max: self.max.component_max(other.max),
+        }
+    }
+
+    /// The smallest rectangle containing this rectangle and the given point
+    pub fn expand_to(&self, p: Point<I>) -> Self {
+        Self {
+            min: self.min.component_min(p),
+            max: self.max.component_max(p),
+        }
+    }
+
+    /// Split into the columns left of `x` and the columns from `x` onwards.
+    /// Either half is None if it would be empty.
+    pub fn split_at_x(&self, x: I) -> (Option<Self>, Option<Self>) {
+        let left = (x > self.min.x).then(|| Self {
+            min: self.min,
+            max: Point::new(min_of(x - I::one(), self.max.x), self.max.y),
+        });
+        let right = (x <= self.max.x).then(|| Self {
+            min: Point::new(max_of(x, self.min.x), self.min.y),
+            max: self.max,
+        });
+        (left, right)
+    }
+
+    /// Split into the rows above `y` and the rows from `y` onwards. Either half
+    /// is None if it would be empty.
+    pub fn split_at_y(&self, y: I) -> (Option<Self>, Option<Self>) {
+        let (top, bottom) = self.transpose().split_at_x(y);
+        (top.map(|r| r.transpose()), bottom.map(|r| r.transpose()))
+    }
+
+    fn transpose(&self) -> Self {
+        Self {
+            min: self.min.transpose(),
+            max: self.max.transpose(),
+        }
+    }
+
+    /// Iterate over every point in the rectangle in row-major order
+    pub fn points(&self) -> RectPoints<I> {
+        RectPoints {
+            rect: *self,
+            next: Some(self.min),
+        }
+    }
+}
+
+fn min_of<I: DimVal>(a: I, b: I) -> I {
+    if a < b { a } else { b }
+}
+
+fn max_of<I: DimVal>(a: I, b: I) -> I {
+    if a > b { a } else { b }
+}
+
+impl<I: DimVal> fmt::Display for Rect<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RectPoints<I: DimVal> {
+    rect: Rect<I>,
+    next: Option<Point<I>>,
+}
+
+impl<I: DimVal> Iterator for RectPoints<I> {
+    type Item = Point<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = if current.x < self.rect.max.x {
+            Some(Point::new(current.x + I::one(), current.y))
+        } else if current.y < self.rect.max.y {
+            Some(Point::new(self.rect.min.x, current.y + I::one()))
+        } else {
+            None
+        };
+        Some(current)
+    }
+}
+
+impl<I: DimVal> std::iter::FusedIterator for RectPoints<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Rect};
+
+    #[test]
+    fn test_basic() {
+        let r = Rect::new(Point::new(5, 1), Point::new(2, 3));
+        assert_eq!(r.min, Point::new(2, 1));
+        assert_eq!(r.max, Point::new(5, 3));
+        assert_eq!(r.width(), 4);
+        assert_eq!(r.height(), 3);
+        assert_eq!(r.area(), 12);
+        assert!(r.contains(Point::new(5, 3)));
+        assert!(!r.contains_in_interior(Point::new(5, 2)));
+        assert!(r.contains_in_interior(Point::new(4, 2)));
+        assert_eq!(r.points().count(), 12);
+        assert_eq!(r.points().nth(4), Some(Point::new(2, 2)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = Rect::new(Point::new(0, 0), Point::new(4, 4));
+        let b = Rect::new(Point::new(3, 2), Point::new(6, 8));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point::new(3, 2), Point::new(4, 4)))
+        );
+        assert_eq!(a.union(&b), Rect::new(Point::new(0, 0), Point::new(6, 8)));
+        let c = Rect::new(Point::new(5, 5), Point::new(6, 6));
+        assert!(!a.intersects(&c));
+        assert!(a.union(&b).contains_rect(&c));
+        assert_eq!(
+            Rect::from_points([Point::new(3, -1), Point::new(-2, 4), Point::new(0, 0)]),
+            Some(Rect::new(Point::new(-2, -1), Point::new(3, 4)))
+        );
+        assert_eq!(Rect::<i64>::from_points([]), None);
+    }
+
+    #[test]
+    fn test_split() {
+        let r = Rect::new(Point::new(0, 0), Point::new(4, 2));
+        let (left, right) = r.split_at_x(2);
+        assert_eq!(left, Some(Rect::new(Point::new(0, 0), Point::new(1, 2))));
+        assert_eq!(right, Some(Rect::new(Point::new(2, 0), Point::new(4, 2))));
+        assert_eq!(r.split_at_x(0), (None, Some(r)));
+        assert_eq!(r.split_at_x(5), (Some(r), None));
+        let (top, bottom) = r.split_at_y(1);
+        assert_eq!(top, Some(Rect::new(Point::new(0, 0), Point::new(4, 0))));
+        assert_eq!(bottom, Some(Rect::new(Point::new(0, 1), Point::new(4, 2))));
+    }
+}
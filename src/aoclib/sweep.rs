@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::ops::{Range, RangeInclusive};
+
+use num_traits::PrimInt;
+
+use crate::dimval::DimVal;
+use crate::range_set::RangeSet;
+use crate::rect::Rect;
+
+/// A sweep over a line of items which each become active at a start
+/// coordinate and inactive at an end coordinate.
+///
+/// Spans are half-open: an item added with `start..end` is active at `start`
+/// but not at `end`, so items which end where another starts never overlap.
+#[derive(Debug, Clone)]
+pub struct SweepLine<T, P> {
+    /// (coordinate, is_start, item index), sorted once the sweep begins
+    events: Vec<(T, bool, usize)>,
+    payloads: Vec<P>,
+}
+
+impl<T: Ord + Copy, P> Default for SweepLine<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy, P> SweepLine<T, P> {
+    pub fn new() -> Self {
+        Self {
+            events: vec![],
+            payloads: vec![],
+        }
+    }
+
+    /// Add an item active over `span`. Empty spans are ignored.
+    pub fn add(&mut self, span: Range<T>, payload: P) {
+        if span.start >= span.end {
+            return;
+        }
+        let index = self.payloads.len();
+        self.payloads.push(payload);
+        self.events.push((span.start, true, index));
+        self.events.push((span.end, false, index));
+    }
+
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Iterate over the maximal spans during which the set of active items
+    /// doesn't change, in increasing order. Spans with no active items are
+    /// skipped. Active payloads are listed in the order they were added.
+    pub fn segments(&self) -> Segments<'_, T, P> {
+        let mut events = self.events.clone();
+        // ends sort before starts at the same coordinate
+        events.sort_unstable();
+        Segments {
+            sweep: self,
+            events,
+            next_event: 0,
+            active: BTreeMap::new(),
+        }
+    }
+
+    /// The first span with the greatest number of simultaneously active
+    /// items, or None if there are no items
+    pub fn max_depth(&self) -> Option<(Range<T>, usize)> {
+        let mut best: Option<(Range<T>, usize)> = None;
+        for (span, active) in self.segments() {
+            if best.as_ref().is_none_or(|(_, depth)| active.len() > *depth) {
+                best = Some((span, active.len()));
+            }
+        }
+        best
+    }
+}
+
+impl<T: PrimInt, P> SweepLine<T, P> {
+    /// Add an item active over an inclusive integer range. This is stored as
+    /// the half-open span `start..end + 1`, so it fails if the range ends at
+    /// `T::max_value()`.
+    pub fn add_inclusive(&mut self, range: RangeInclusive<T>, payload: P) -> anyhow::Result<()> {
+        let (start, end) = range.into_inner();
+        let Some(after) = end.checked_add(&T::one()) else {
+            anyhow::bail!("an inclusive range can't end at the largest value of its type");
+        };
+        self.add(start..after, payload);
+        Ok(())
+    }
+}
+
+pub struct Segments<'a, T, P> {
+    sweep: &'a SweepLine<T, P>,
+    events: Vec<(T, bool, usize)>,
+    next_event: usize,
+    active: BTreeMap<usize, &'a P>,
+}
+
+impl<'a, T: Ord + Copy, P> Iterator for Segments<'a, T, P> {
+    type Item = (Range<T>, Vec<&'a P>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (coordinate, _, _) = *self.events.get(self.next_event)?;
+            while let Some(&(c, is_start, index)) = self.events.get(self.next_event)
+                && c == coordinate
+            {
+                if is_start {
+                    self.active.insert(index, &self.sweep.payloads[index]);
+                } else {
+                    self.active.remove(&index);
+                }
+                self.next_event += 1;
+            }
+            let (end, _, _) = *self.events.get(self.next_event)?;
+            if !self.active.is_empty() {
+                return Some((coordinate..end, self.active.values().copied().collect()));
+            }
+        }
+    }
+}
+
+impl<T: Ord + Copy, P> std::iter::FusedIterator for Segments<'_, T, P> {}
+
+impl<I: DimVal + PrimInt> Rect<I> {
+    /// The number of points covered by at least one of the rectangles, or
+    /// None if that doesn't fit in an `I`. Rectangles may not reach
+    /// `I::max_value()` on the x axis; see [`SweepLine::add_inclusive`].
+    pub fn union_area<It: IntoIterator<Item = Rect<I>>>(rects: It) -> Option<I> {
+        let mut sweep = SweepLine::new();
+        for r in rects {
+            sweep
+                .add_inclusive(r.min.x..=r.max.x, r.min.y..=r.max.y)
+                .ok()?;
+        }
+        sweep.segments().try_fold(I::zero(), |area, (xs, active)| {
+            let ys = active.into_iter().cloned().collect::<RangeSet<I>>();
+            area.checked_add(&(xs.end - xs.start).checked_mul(&ys.covered_len()?)?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SweepLine;
+    use crate::point::Point;
+    use crate::rect::Rect;
+
+    #[test]
+    fn test_segments() {
+        let mut sweep = SweepLine::new();
+        sweep.add(0..4, 'a');
+        sweep.add(2..6, 'b');
+        sweep.add(6..8, 'c');
+        sweep.add(10..12, 'd');
+        sweep.add(3..3, 'e');
+        assert_eq!(sweep.len(), 4);
+        let segments = sweep
+            .segments()
+            .map(|(span, active)| (span, active.into_iter().collect::<String>()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segments,
+            vec![
+                (0..2, "a".to_string()),
+                (2..4, "ab".to_string()),
+                (4..6, "b".to_string()),
+                (6..8, "c".to_string()),
+                (10..12, "d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut sweep = SweepLine::new();
+        assert_eq!(sweep.max_depth(), None);
+        for r in [3..=5, 10..=14, 16..=20, 12..=18, 13..=13] {
+            sweep.add_inclusive(r, ()).unwrap();
+        }
+        assert_eq!(sweep.max_depth(), Some((13..14, 3)));
+        assert!(sweep.add_inclusive(250u8..=255, ()).is_err());
+        assert_eq!(sweep.len(), 5);
+    }
+
+    #[test]
+    fn test_union_area() {
+        let rects = [
+            Rect::new(Point::new(0, 0), Point::new(3, 3)),
+            Rect::new(Point::new(2, 2), Point::new(5, 5)),
+            Rect::new(Point::new(10, 0), Point::new(10, 0)),
+        ];
+        let brute = rects
+            .iter()
+            .flat_map(|r| r.points())
+            .collect::<std::collections::BTreeSet<_>>()
+            .len();
+        assert_eq!(Rect::union_area(rects), Some(brute as i64));
+        assert_eq!(Rect::union_area(rects), Some(16 + 16 - 4 + 1));
+        let edge = Rect::new(Point::new(0i8, 0), Point::new(i8::MAX, 0));
+        assert_eq!(Rect::union_area([edge]), None);
+    }
+}
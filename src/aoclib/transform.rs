@@ -0,0 +1,232 @@
+use std::fmt;
+
+use crate::dimval::DimVal;
+use crate::grid::DenseGrid;
+use crate::point::{Point, Rotation};
+
+/// A 2D integer affine transform, mapping `(x, y)` to
+/// `(xx * x + xy * y + tx, yx * x + yy * y + ty)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AffineTransform<I: DimVal = i64> {
+    pub xx: I,
+    pub xy: I,
+    pub yx: I,
+    pub yy: I,
+    pub tx: I,
+    pub ty: I,
+}
+
+impl<I: DimVal> AffineTransform<I> {
+    pub fn new(xx: I, xy: I, yx: I, yy: I, tx: I, ty: I) -> Self {
+        Self {
+            xx,
+            xy,
+            yx,
+            yy,
+            tx,
+            ty,
+        }
+    }
+
+    pub fn identity() -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(one, zero, zero, one, zero, zero)
+    }
+
+    pub fn translation(offset: Point<I>) -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(one, zero, zero, one, offset.x, offset.y)
+    }
+
+    pub fn scale(factor: I) -> Self {
+        let zero = I::zero();
+        Self::new(factor, zero, zero, factor, zero, zero)
+    }
+
+    /// Rotation about the origin; matches [`Point::rotate_by`]
+    pub fn rotation(r: Rotation) -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        match r {
+            Rotation::CW => Self::new(zero, -one, one, zero, zero, zero),
+            Rotation::CCW => Self::new(zero, one, -one, zero, zero, zero),
+            Rotation::Mirror => Self::new(-one, zero, zero, -one, zero, zero),
+        }
+    }
+
+    pub fn rotation_about(r: Rotation, center: Point<I>) -> Self {
+        Self::translation(-center)
+            .then(&Self::rotation(r))
+            .then(&Self::translation(center))
+    }
+
+    /// Reflect across the y axis (`x -> -x`)
+    pub fn reflect_x() -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(-one, zero, zero, one, zero, zero)
+    }
+
+    /// Reflect across the x axis (`y -> -y`)
+    pub fn reflect_y() -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(one, zero, zero, -one, zero, zero)
+    }
+
+    /// Reflect across the line `y = x`
+    pub fn transpose() -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(zero, one, one, zero, zero, zero)
+    }
+
+    /// Reflect across the line `y = -x`
+    pub fn anti_transpose() -> Self {
+        let (zero, one) = (I::zero(), I::one());
+        Self::new(zero, -one, -one, zero, zero, zero)
+    }
+
+    pub fn determinant(&self) -> I {
+        self.xx * self.yy - self.xy * self.yx
+    }
+
+    /// Compose two transforms: the result applies `self`, then `next`
+    pub fn then(&self, next: &Self) -> Self {
+        Self::new(
+            next.xx * self.xx + next.xy * self.yx,
+            next.xx * self.xy + next.xy * self.yy,
+            next.yx * self.xx + next.yy * self.yx,
+            next.yx * self.xy + next.yy * self.yy,
+            next.xx * self.tx + next.xy * self.ty + next.tx,
+            next.yx * self.tx + next.yy * self.ty + next.ty,
+        )
+    }
+
+    /// The inverse transform, if it has integer coefficients (i.e., the
+    /// determinant is 1 or -1)
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det != I::one() && det != -I::one() {
+            return None;
+        }
+        // for det = ±1, dividing by det is the same as multiplying by it
+        let xx = self.yy * det;
+        let xy = -self.xy * det;
+        let yx = -self.yx * det;
+        let yy = self.xx * det;
+        Some(Self::new(
+            xx,
+            xy,
+            yx,
+            yy,
+            -(xx * self.tx + xy * self.ty),
+            -(yx * self.tx + yy * self.ty),
+        ))
+    }
+
+    pub fn apply(&self, p: Point<I>) -> Point<I> {
+        Point::new(
+            self.xx * p.x + self.xy * p.y + self.tx,
+            self.yx * p.x + self.yy * p.y + self.ty,
+        )
+    }
+
+    pub fn apply_all<'a, It>(&'a self, points: It) -> impl Iterator<Item = Point<I>> + 'a
+    where
+        It: IntoIterator<Item = Point<I>>,
+        It::IntoIter: 'a,
+    {
+        points.into_iter().map(|p| self.apply(p))
+    }
+
+    /// Does this transform map the integer grid onto itself one-to-one (i.e.,
+    /// is the linear part a rotation and/or reflection)?
+    pub fn preserves_grid(&self) -> bool {
+        let zero = I::zero();
+        let unit = |v: I| v == I::one() || v == -I::one();
+        (self.xy == zero && self.yx == zero && unit(self.xx) && unit(self.yy))
+            || (self.xx == zero && self.yy == zero && unit(self.xy) && unit(self.yx))
+    }
+}
+
+impl<I: DimVal> std::ops::Mul for AffineTransform<I> {
+    type Output = Self;
+
+    /// Matrix-style composition: `(a * b).apply(p) == a.apply(b.apply(p))`
+    fn mul(self, rhs: Self) -> Self {
+        rhs.then(&self)
+    }
+}
+
+impl<I: DimVal> Point<I> {
+    pub fn rotate_about(&self, center: Point<I>, r: Rotation) -> Self {
+        (*self - center).rotate_by(r) + center
+    }
+
+    pub fn transform(&self, t: &AffineTransform<I>) -> Self {
+        t.apply(*self)
+    }
+}
+
+impl<V: Clone + fmt::Debug> DenseGrid<V> {
+    /// Produce a new grid with every cell moved by the given transform. Only
+    /// transforms which map cells one-to-one (rotations, reflections, and
+    /// translations) are supported.
+    pub fn transformed(&self, t: &AffineTransform) -> anyhow::Result<Self> {
+        if !t.preserves_grid() {
+            anyhow::bail!("transform {:?} does not map the grid onto itself", t);
+        }
+        let a = t.apply(self.bounds().min);
+        let b = t.apply(self.bounds().max);
+        let mut out = DenseGrid::new_with(a, b, self[self.origin()].clone());
+        for (p, v) in self.iter() {
+            out[t.apply(p)] = v.clone();
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AffineTransform, DenseGrid, Point, Rotation};
+
+    #[test]
+    fn test_rotate_about() {
+        let center = Point::new(2, 3);
+        assert_eq!(
+            Point::new(4, 4).rotate_about(center, Rotation::CW),
+            Point::new(1, 5)
+        );
+        let t = AffineTransform::rotation_about(Rotation::CW, center);
+        assert_eq!(t.apply(Point::new(4, 4)), Point::new(1, 5));
+        assert_eq!(t.apply(center), center);
+    }
+
+    #[test]
+    fn test_compose_and_inverse() {
+        let t = AffineTransform::rotation(Rotation::CCW)
+            .then(&AffineTransform::translation(Point::new(5, -2)))
+            .then(&AffineTransform::transpose());
+        let inv = t.inverse().unwrap();
+        for p in [Point::new(0, 0), Point::new(3, 7), Point::new(-4, 1)] {
+            assert_eq!(inv.apply(t.apply(p)), p);
+        }
+        assert_eq!(t * inv, AffineTransform::identity());
+        assert_eq!(AffineTransform::scale(2).inverse(), None);
+        let reflect = AffineTransform::<i64>::reflect_x() * AffineTransform::reflect_y();
+        assert_eq!(reflect, AffineTransform::rotation(Rotation::Mirror));
+        assert_eq!(
+            AffineTransform::anti_transpose().apply(Point::new(1, 2)),
+            Point::new(-2, -1)
+        );
+    }
+
+    #[test]
+    fn test_transform_grid() {
+        let g = DenseGrid::from_input("ab\ncd\nef", |c| c);
+        let rotated = g
+            .transformed(&AffineTransform::rotation(Rotation::CW))
+            .unwrap();
+        assert_eq!(rotated.origin(), Point::new(-2, 0));
+        assert_eq!(rotated.to_string(), "eca\nfdb\n");
+        assert!(g.transformed(&AffineTransform::scale(2)).is_err());
+    }
+}
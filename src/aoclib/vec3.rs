@@ -1,7 +1,10 @@
 use crate::dimval::DimVal;
+use anyhow::Context;
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec3<T: DimVal = i64> {
     pub x: T,
     pub y: T,
@@ -19,6 +22,68 @@ impl<T: DimVal> Vec3<T> {
     pub fn new(x: T, y: T, z: T) -> Self {
         Self { x, y, z }
     }
+
+    pub fn manhattan_distance(&self, other: Vec3<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn squared_euclidean_distance(&self, other: Vec3<T>) -> T {
+        let d = *self - other;
+        d.dot(d)
+    }
+
+    /// The straight-line distance, or None if the squared distance can't be
+    /// converted to an `f64`
+    pub fn euclidean_distance(&self, other: Vec3<T>) -> Option<f64> {
+        self.squared_euclidean_distance(other)
+            .to_f64()
+            .map(f64::sqrt)
+    }
+
+    pub fn dot(&self, other: Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The cross product, perpendicular to both vectors (right-handed)
+    pub fn cross(&self, other: Vec3<T>) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// The 6 points sharing a face with this one, when treated as a unit cube
+    pub fn face_neighbors_array(&self) -> [Self; 6] {
+        let (zero, one) = (T::zero(), T::one());
+        [
+            *self + Self::new(one, zero, zero),
+            *self + Self::new(zero, one, zero),
+            *self + Self::new(zero, zero, one),
+            *self - Self::new(one, zero, zero),
+            *self - Self::new(zero, one, zero),
+            *self - Self::new(zero, zero, one),
+        ]
+    }
+
+    /// The 26 points sharing a face, edge, or corner with this one
+    pub fn all_neighbors_array(&self) -> [Self; 26] {
+        let steps = [T::zero() - T::one(), T::zero(), T::one()];
+        let mut out = [*self; 26];
+        let mut i = 0;
+        for dx in steps {
+            for dy in steps {
+                for dz in steps {
+                    if dx.is_zero() && dy.is_zero() && dz.is_zero() {
+                        continue;
+                    }
+                    out[i] = *self + Self::new(dx, dy, dz);
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
 }
 
 impl<T: DimVal + std::ops::Add> std::ops::Add for Vec3<T> {
@@ -53,10 +118,10 @@ impl<T: DimVal + std::ops::Sub> std::ops::Sub for Vec3<T> {
     }
 }
 
-impl<T: DimVal + std::ops::Mul<i64, Output = T>> std::ops::Mul<i64> for Vec3<T> {
+impl<T: DimVal> std::ops::Mul<T> for Vec3<T> {
     type Output = Vec3<T>;
 
-    fn mul(self, scalar: i64) -> Self::Output {
+    fn mul(self, scalar: T) -> Self::Output {
         Self::Output {
             x: self.x * scalar,
             y: self.y * scalar,
@@ -65,8 +130,102 @@ impl<T: DimVal + std::ops::Mul<i64, Output = T>> std::ops::Mul<i64> for Vec3<T>
     }
 }
 
-impl fmt::Display for Vec3 {
+impl<T: DimVal> std::ops::Neg for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn neg(self) -> Self::Output {
+        Self::Output {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<T: DimVal> fmt::Display for Vec3<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {}, {})", self.x, self.y, self.z)
     }
 }
+
+/// Parses `"x,y,z"`, allowing whitespace around each component
+impl<T> FromStr for Vec3<T>
+where
+    T: DimVal + FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.trim().split(',').collect::<Vec<_>>();
+        let [x, y, z] = parts[..] else {
+            anyhow::bail!("expected 3 comma-separated values, found {:?}", s);
+        };
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<T>()
+                .with_context(|| format!("invalid coordinate {:?} in {:?}", v, s))
+        };
+        Ok(Self::new(parse(x)?, parse(y)?, parse(z)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vec3;
+
+    #[test]
+    fn test_distances() {
+        let a = Vec3::new(1, 2, 3);
+        let b = Vec3::new(4, -2, 3);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.squared_euclidean_distance(b), 25);
+        assert_eq!(a.euclidean_distance(b), Some(5.0));
+    }
+
+    #[test]
+    fn test_products() {
+        let x = Vec3::new(1, 0, 0);
+        let y = Vec3::new(0, 1, 0);
+        assert_eq!(x.dot(y), 0);
+        assert_eq!(x.cross(y), Vec3::new(0, 0, 1));
+        assert_eq!(y.cross(x), -Vec3::new(0, 0, 1));
+        assert_eq!(Vec3::new(1, 2, 3).dot(Vec3::new(4, 5, 6)), 32);
+        assert_eq!(Vec3::new(1i32, -2, 3) * 2, Vec3::new(2, -4, 6));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Vec3::new(5, 5, 5);
+        let faces = p.face_neighbors_array();
+        assert!(faces.iter().all(|n| n.manhattan_distance(p) == 1));
+        let all = p.all_neighbors_array();
+        let unique = all.iter().collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(unique.len(), 26);
+        assert!(!all.contains(&p));
+        assert!(faces.iter().all(|n| all.contains(n)));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let v: Vec3 = "162,817,-812".parse().unwrap();
+        assert_eq!(v, Vec3::new(162, 817, -812));
+        assert_eq!(v.to_string(), "(162, 817, -812)");
+        assert_eq!(
+            " 1, 2 ,3 ".parse::<Vec3<i32>>().unwrap(),
+            Vec3::new(1, 2, 3)
+        );
+        assert!("1,2".parse::<Vec3>().is_err());
+        assert!("1,2,3,4".parse::<Vec3>().is_err());
+        assert!("1,x,3".parse::<Vec3>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let v = Vec3::new(1, -2, 3);
+        let s = serde_json::to_string(&v).unwrap();
+        assert_eq!(s, r#"{"x":1,"y":-2,"z":3}"#);
+        assert_eq!(serde_json::from_str::<Vec3>(&s).unwrap(), v);
+    }
+}
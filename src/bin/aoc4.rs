@@ -27,7 +27,7 @@ fn read_input() -> DenseGrid<Cell> {
 fn compute_neighbors(grid: &DenseGrid<Cell>) -> DenseGrid<u8> {
     let mut adjacencies = DenseGrid::new_with_dimensions_from(grid, 0);
     for (coordinate, value) in grid.iter() {
-        if value != Cell::Full {
+        if *value != Cell::Full {
             continue;
         }
         for neighbor in coordinate.all_neighbors_array() {
@@ -43,7 +43,7 @@ fn part1(grid: &DenseGrid<Cell>) -> usize {
     let adjacencies = compute_neighbors(grid);
     grid.iter()
         .filter(|(coordinate, value)| {
-            if *value == Cell::Full {
+            if **value == Cell::Full {
                 adjacencies[*coordinate] < 4
             } else {
                 false
@@ -58,7 +58,7 @@ fn part2(mut grid: DenseGrid<Cell>) -> usize {
     loop {
         let mut removed_this_round = BTreeSet::new();
         for (coordinate, value) in grid.iter() {
-            if value == Cell::Full {
+            if *value == Cell::Full {
                 let count = adjacencies[coordinate];
                 if count < 4 {
                     removed_this_round.insert(coordinate);
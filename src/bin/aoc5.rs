@@ -1,9 +1,9 @@
+use aoclib::RangeSet;
 use std::io::BufRead;
-use std::ops::RangeInclusive;
 
 #[derive(Debug)]
 struct Db {
-    fresh: Vec<RangeInclusive<u64>>,
+    fresh: RangeSet<u64>,
     ingredients: Vec<u64>,
 }
 
@@ -11,32 +11,14 @@ impl Db {
     fn part1(&self) -> usize {
         self.ingredients
             .iter()
-            .filter(|i| self.fresh.iter().any(|r| r.contains(i)))
+            .filter(|i| self.fresh.contains(**i))
             .count()
     }
 
     fn part2(&self) -> u64 {
-        let mut current: Option<RangeInclusive<u64>> = None;
-        let mut ranges = vec![];
-        for range in &self.fresh {
-            if let Some(c) = current {
-                assert!(range.start() >= c.start());
-                if *range.start() > *c.end() {
-                    ranges.push(c);
-                    current = Some(range.clone());
-                } else {
-                    let new_start = std::cmp::min(*c.start(), *range.start());
-                    let new_end = std::cmp::max(*c.end(), *range.end());
-                    current = Some(new_start..=new_end);
-                }
-            } else {
-                current = Some(range.clone());
-            }
-        }
-        if let Some(current) = current.take() {
-            ranges.push(current);
-        }
-        ranges.iter().map(|r| r.end() - r.start() + 1).sum()
+        self.fresh
+            .covered_len()
+            .expect("too many fresh ingredients to count")
     }
 }
 
@@ -45,7 +27,7 @@ fn read_input() -> anyhow::Result<Db> {
     let stdin_lock = stdin.lock();
     let mut in_ingredients = false;
     let mut ingredients = vec![];
-    let mut fresh = vec![];
+    let mut fresh = RangeSet::new();
     for line in stdin_lock.lines() {
         let line = line?;
         let line = line.trim();
@@ -59,11 +41,10 @@ fn read_input() -> anyhow::Result<Db> {
             };
             let start = start.parse()?;
             let end = end.parse()?;
-            fresh.push(start..=end);
+            fresh.insert(start..=end);
         }
     }
     ingredients.sort();
-    fresh.sort_unstable_by_key(|r| (*r.start(), *r.end()));
     Ok(Db { fresh, ingredients })
 }
 
@@ -1,4 +1,4 @@
-use aoclib::{DenseGrid, Point};
+use aoclib::{DenseGrid, Direction, Point};
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::{Arc, Mutex};
 
@@ -55,7 +55,7 @@ fn part1(g: &DenseGrid<Cell>) -> usize {
 fn part2(g: &DenseGrid<Cell>) -> usize {
     let start = g.find(&Cell::Start).unwrap();
     let cache = Arc::new(Mutex::new(BTreeMap::new()));
-    cached_points_to_end_from(g, start + Point::new(0, 1), cache)
+    cached_points_to_end_from(g, start + Direction::S, cache)
 }
 
 fn cached_points_to_end_from(
@@ -76,26 +76,32 @@ fn cached_points_to_end_from(
 
 fn points_to_end_from(
     g: &DenseGrid<Cell>,
-    mut point: Point,
+    point: Point,
     cache: Arc<Mutex<BTreeMap<Point, usize>>>,
 ) -> usize {
-    loop {
-        match g.get(point) {
-            Some(Cell::Empty) => point = point + Point::new(0, 1),
-            Some(Cell::Splitter) => {
-                let left = point + Point::new(-1, 0);
-                let right = point + Point::new(1, 0);
-                let mut sum = 0;
-                if g.get(left) == Some(Cell::Empty) {
-                    sum += cached_points_to_end_from(g, left, Arc::clone(&cache));
-                }
-                if g.get(right) == Some(Cell::Empty) {
-                    sum += cached_points_to_end_from(g, right, Arc::clone(&cache));
-                }
-                return sum;
+    let down = Direction::S.to_point();
+    // the beam covers `point` itself, which the ray doesn't include
+    let hit = match g.get(point) {
+        Some(Cell::Empty) => g
+            .cast_ray(point, down, |_, c| *c != Cell::Empty)
+            .map(|(p, c)| (p, *c)),
+        other => other.map(|c| (point, c)),
+    };
+    match hit {
+        None => 1,
+        Some((_, Cell::Start)) => 0,
+        // the ray never stops at an empty cell, so this is a splitter
+        Some((point, Cell::Splitter | Cell::Empty)) => {
+            let left = point + Direction::W;
+            let right = point + Direction::E;
+            let mut sum = 0;
+            if g.get(left) == Some(Cell::Empty) {
+                sum += cached_points_to_end_from(g, left, Arc::clone(&cache));
+            }
+            if g.get(right) == Some(Cell::Empty) {
+                sum += cached_points_to_end_from(g, right, Arc::clone(&cache));
             }
-            Some(Cell::Start) => return 0,
-            None => return 1,
+            sum
         }
     }
 }